@@ -0,0 +1,158 @@
+//! Assembles the application from explicit dependencies.
+
+use super::VimSessionManager;
+use crate::cli::Cli;
+use crate::config::{TomlConfigFile, ENVIRONMENT};
+use crate::error::VsmRuntimeFault;
+use crate::session::SessionDirs;
+use crate::ui::{Prompt, UserPromptRenderer};
+use crate::utils::{is_interactive, CommandExecutor, FilesystemManager, Shell};
+use std::path::PathBuf;
+
+/// Builds a `VimSessionManager` with explicit dependencies. Anything left
+/// unset falls back to what `VimSessionManager::new` would use.
+#[derive(Debug, Default)]
+pub struct VimSessionManagerBuilder {
+    /// Command line arguments, including the binary name
+    args: Option<Vec<String>>,
+    /// Absolute path to the session storage directory
+    session_dir: Option<String>,
+    /// Absolute path to the configuration file
+    config_file: Option<String>,
+    /// Absolute path to the data directory
+    data_dir: Option<String>,
+    /// Directory project-local sessions are discovered from
+    working_dir: Option<String>,
+    /// Home directory paths in sessions are spelled relative to
+    home: Option<String>,
+    /// Whether the user can be prompted
+    interactive: Option<bool>,
+    /// Probes for vim variants and launches the editor
+    shell: Option<Box<dyn Shell>>,
+    /// Asks the user questions
+    prompt: Option<Box<dyn Prompt>>,
+}
+
+impl VimSessionManagerBuilder {
+    /// Command line arguments to parse instead of the process arguments, the
+    /// first of which is the binary name.
+    #[must_use]
+    pub fn args<I, T>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.args = Some(args.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Session directory to use instead of `$VIM_SESSIONS`.
+    #[must_use]
+    pub fn session_dir(mut self, session_dir: &str) -> Self {
+        self.session_dir = Some(String::from(session_dir));
+        self
+    }
+
+    /// Configuration file to use instead of `~/.config/vsm/config.toml`, its
+    /// parent directory is created when the file is written.
+    #[must_use]
+    pub fn config_file(mut self, config_file: &str) -> Self {
+        self.config_file = Some(String::from(config_file));
+        self
+    }
+
+    /// Data directory to use instead of `~/.local/share/vsm`, it holds the
+    /// session metadata.
+    #[must_use]
+    pub fn data_dir(mut self, data_dir: &str) -> Self {
+        self.data_dir = Some(String::from(data_dir));
+        self
+    }
+
+    /// Directory to discover project-local sessions from instead of the
+    /// working directory of the process.
+    #[must_use]
+    pub fn working_dir(mut self, working_dir: &str) -> Self {
+        self.working_dir = Some(String::from(working_dir));
+        self
+    }
+
+    /// Home directory to use instead of `$HOME`, `~` in sessions stands for
+    /// it.
+    #[must_use]
+    pub fn home(mut self, home: &str) -> Self {
+        self.home = Some(String::from(home));
+        self
+    }
+
+    /// Whether the prompt may ask questions, instead of checking if stdin and
+    /// stdout are a terminal. Without it, questions that need an answer fail
+    /// and ask for the flag that answers them.
+    #[must_use]
+    pub const fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = Some(interactive);
+        self
+    }
+
+    /// Shell used to probe for vim variants and to launch the editor.
+    #[must_use]
+    pub fn shell(mut self, shell: Box<dyn Shell>) -> Self {
+        self.shell = Some(shell);
+        self
+    }
+
+    /// Prompt implementation used to ask the user questions.
+    #[must_use]
+    pub fn prompt(mut self, prompt: Box<dyn Prompt>) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    /// Builds the application.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::CommandLine if the arguments can not be parsed.
+    pub fn build(mut self) -> Result<VimSessionManager, VsmRuntimeFault> {
+        let cli = match self.args.take() {
+            Some(args) => Cli::try_from_args(args)?,
+            None => Cli::new(),
+        };
+        Ok(self.assemble(cli))
+    }
+
+    /// Fills in every unset dependency and builds the application.
+    pub(super) fn assemble(self, cli: Cli) -> VimSessionManager {
+        let config_file = self
+            .config_file
+            .unwrap_or_else(|| ENVIRONMENT.path().vsm_config_file().clone());
+        let session_dir = self
+            .session_dir
+            .unwrap_or_else(|| ENVIRONMENT.var().vim_sessions().clone());
+
+        VimSessionManager {
+            cli,
+            config_file_struct: TomlConfigFile::default(),
+            fs: FilesystemManager::new(&config_file),
+            dirs: SessionDirs::from_list(&session_dir),
+            data_dir: PathBuf::from(
+                self.data_dir
+                    .unwrap_or_else(|| ENVIRONMENT.path().vsm_data_dir().clone()),
+            ),
+            working_dir: self.working_dir.map_or_else(
+                || std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+                PathBuf::from,
+            ),
+            home: self
+                .home
+                .unwrap_or_else(|| ENVIRONMENT.var().home().clone()),
+            interactive: self.interactive.unwrap_or_else(is_interactive),
+            shell: self
+                .shell
+                .unwrap_or_else(|| Box::new(CommandExecutor::new())),
+            prompt: self
+                .prompt
+                .unwrap_or_else(|| Box::new(UserPromptRenderer::new())),
+            first_run: true,
+        }
+    }
+}
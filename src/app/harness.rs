@@ -0,0 +1,174 @@
+//! Test doubles and a sandbox for driving the application.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use tempfile::TempDir;
+
+use crate::app::{VimSessionManager, VimSessionManagerBuilder};
+use crate::error::VsmRuntimeFault;
+use crate::testing::test_dir;
+use crate::ui::{PickerEntry, Prompt};
+use crate::utils::Shell;
+
+/// Pretends only some programs are installed, and records every session
+/// it is asked to open instead of spawning an editor, along with what the
+/// session held. With `saves` the session is written over, as an editor
+/// saving it would.
+#[derive(Debug, Default)]
+pub(super) struct FakeShell {
+    pub(super) installed: Vec<String>,
+    pub(super) opened: Rc<RefCell<Vec<String>>>,
+    pub(super) sourced: Rc<RefCell<Vec<String>>>,
+    pub(super) saves: Option<String>,
+}
+
+impl Shell for FakeShell {
+    fn is_installed(&self, program: &str) -> bool {
+        self.installed.iter().any(|p| p == program)
+    }
+
+    fn open_editor_with_session(
+        &self,
+        vim_variant: &str,
+        _shell_command: &str,
+        session_file: &str,
+        commands: &[String],
+    ) -> Result<Option<i32>, VsmRuntimeFault> {
+        let mut launched = format!("{} {}", vim_variant, session_file);
+        for command in commands {
+            launched.push_str(&format!(" -c {}", command));
+        }
+        self.opened.borrow_mut().push(launched);
+        self.sourced
+            .borrow_mut()
+            .push(fs::read_to_string(session_file).unwrap_or_default());
+        if let Some(contents) = self.saves.as_ref() {
+            fs::write(session_file, contents).expect("session is saved");
+        }
+        Ok(Some(0))
+    }
+
+    fn launch_editor(
+        &self,
+        vim_variant: &str,
+        arguments: &[String],
+        _cwd: Option<&Path>,
+    ) -> Result<(), VsmRuntimeFault> {
+        self.opened
+            .borrow_mut()
+            .push(format!("{} {}", vim_variant, arguments.join(" ")));
+        Ok(())
+    }
+}
+
+/// Answers the variant prompt, and fails every other question.
+#[derive(Debug)]
+pub(super) struct FakePrompt {
+    pub(super) variant: String,
+}
+
+impl Prompt for FakePrompt {
+    fn vim_variant(&self, vim_variations: Vec<String>) -> Result<String, VsmRuntimeFault> {
+        vim_variations
+            .into_iter()
+            .find(|v| *v == self.variant)
+            .ok_or_else(|| VsmRuntimeFault::SelectionFailure {
+                msg: "variant not offered".to_owned(),
+            })
+    }
+
+    fn session_open(&self, _sessions: &[PickerEntry]) -> Result<String, VsmRuntimeFault> {
+        Err(VsmRuntimeFault::SelectionFailure {
+            msg: "unexpected prompt".to_owned(),
+        })
+    }
+
+    fn session_remove(&self, _sessions: &[PickerEntry]) -> Result<Vec<String>, VsmRuntimeFault> {
+        Err(VsmRuntimeFault::SelectionFailure {
+            msg: "unexpected prompt".to_owned(),
+        })
+    }
+
+    fn text(&self, _message: &str, _initial: &str) -> Result<String, VsmRuntimeFault> {
+        Err(VsmRuntimeFault::SelectionFailure {
+            msg: "unexpected prompt".to_owned(),
+        })
+    }
+
+    fn confirm(&self, _message: &str) -> Result<bool, VsmRuntimeFault> {
+        Ok(false)
+    }
+
+    fn choose(&self, _message: &str, _options: &[&str]) -> Result<String, VsmRuntimeFault> {
+        Err(VsmRuntimeFault::SelectionFailure {
+            msg: "unexpected prompt".to_owned(),
+        })
+    }
+}
+
+/// Creates a fresh directory with a `sessions` directory holding the
+/// given sessions, and optionally a config.toml selecting vim.
+pub(super) fn sandbox(test: &str, sessions: &[&str], configured: bool) -> TempDir {
+    let dir = test_dir(&format!("app-{}", test));
+    let root = dir.path();
+    fs::create_dir_all(root.join("sessions")).expect("sandbox is created");
+    for session in sessions {
+        fs::write(root.join("sessions").join(session), "").expect("session is written");
+    }
+    if configured {
+        fs::create_dir_all(root.join("config")).expect("config dir is created");
+        fs::write(
+            root.join("config").join("config.toml"),
+            "[vim_variant]\nactive_variant = \"vim\"\nshell_command = \"-S\"\n",
+        )
+        .expect("config is written");
+    }
+    dir
+}
+
+/// Builds an application kept inside a sandbox, see `builder`.
+pub(super) fn manager(root: &Path, args: &[&str], shell: FakeShell) -> VimSessionManager {
+    builder(root, args, shell)
+        .build()
+        .expect("arguments are valid")
+}
+
+/// Starts building an application kept inside a sandbox, see `sandbox`. Its
+/// home directory is `home` in the sandbox, and there is no terminal to
+/// prompt on.
+pub(super) fn builder(root: &Path, args: &[&str], shell: FakeShell) -> VimSessionManagerBuilder {
+    VimSessionManager::builder()
+        .args(args.iter().copied())
+        .session_dir(&root.join("sessions").to_string_lossy())
+        .config_file(&root.join("config").join("config.toml").to_string_lossy())
+        .data_dir(&root.join("data").to_string_lossy())
+        .working_dir(&root.to_string_lossy())
+        .home(&root.join("home").to_string_lossy())
+        .interactive(false)
+        .shell(Box::new(shell))
+        .prompt(Box::new(FakePrompt {
+            variant: "nvim".to_owned(),
+        }))
+}
+
+/// Runs an application kept inside a sandbox from another working
+/// directory, see `builder`. Every session it opens is recorded in `opened`.
+pub(super) fn run_in(
+    root: &Path,
+    args: &[&str],
+    working_dir: &Path,
+    opened: &Rc<RefCell<Vec<String>>>,
+) -> Result<(), VsmRuntimeFault> {
+    let shell = FakeShell {
+        opened: Rc::clone(opened),
+        ..FakeShell::default()
+    };
+    builder(root, args, shell)
+        .working_dir(&working_dir.to_string_lossy())
+        .build()
+        .expect("arguments are valid")
+        .run()
+}
@@ -0,0 +1,150 @@
+//! Lists, shows and checks sessions without changing them.
+
+use super::rewrite::warn_unportable;
+use super::VimSessionManager;
+use crate::cli::ArgumentName;
+use crate::error::VsmRuntimeFault;
+use crate::session::{SessionDetails, SessionHealth, SessionRecord};
+use crate::ui::{grouped, render_details, ListFormat};
+use log::{debug, info, warn};
+use std::fs;
+use std::path::PathBuf;
+
+/// Executes sub-command list. Without a `--format` the names are logged,
+/// otherwise the listing is written to stdout undecorated.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionFileRead if a session can not be inspected.
+///     - VsmRuntimeFault::Serialization if the listing can not be rendered.
+pub(super) fn list(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Listing all sessions");
+    let metadata = app.metadata()?;
+    let mut listed = app.sorted(sessions)?;
+    if app.cli.active_command().arg().flag(ArgumentName::PINNED) {
+        listed.retain(|session| {
+            metadata
+                .get(&app.key_of(session))
+                .is_some_and(|m| *m.pinned())
+        });
+    }
+    let Some(format) = app.cli.active_command().arg().option(ArgumentName::FORMAT) else {
+        // Grouped sessions are listed below a header naming their group
+        let mut header: Option<String> = None;
+        for entry in grouped(&app.picker_entries(&listed)?, false) {
+            match entry.group() {
+                Some(group) => {
+                    if header.as_deref() != Some(group) {
+                        info!("{}/", group);
+                        header = Some(group.to_owned());
+                    }
+                    info!("  {}", entry.without_group());
+                }
+                None => {
+                    header = None;
+                    info!("{}", entry);
+                }
+            }
+        }
+        return Ok(());
+    };
+
+    let mut records: Vec<SessionRecord> = vec![];
+    for session in &listed {
+        match SessionRecord::from_path_in(session, &app.dirs) {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                return Err(VsmRuntimeFault::SessionFileRead {
+                    msg: format!("{} => {}", session.display(), e),
+                })
+            }
+        }
+    }
+    let rendered = format.parse::<ListFormat>()?.render(&records, &metadata)?;
+    print!("{}", rendered);
+    Ok(())
+}
+
+/// Executes sub-command show, the details are written to stdout.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionFileRead if the session can not be read.
+///     - VsmRuntimeFault::Serialization if the details can not be rendered.
+pub(super) fn show(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Showing a session");
+    let session = app.choose_session(sessions)?;
+    let details = SessionDetails::inspect(session, &app.dirs, &app.home)?;
+    let json = app.cli.active_command().arg().flag(ArgumentName::JSON);
+    print!("{}", render_details(&details, json)?);
+    Ok(())
+}
+
+/// Executes sub-command check. Problems are logged, with `--only-broken`
+/// only the names of broken sessions are written to stdout. Paths of
+/// portable sessions that stay absolute are warned about, but do not fail
+/// the check.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionCheck if any session has a problem.
+pub(super) fn check(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Checking all sessions");
+    let only_broken = app
+        .cli
+        .active_command()
+        .arg()
+        .flag(ArgumentName::ONLY_BROKEN);
+    let mut broken: usize = 0;
+    for session in sessions {
+        let name = app.name_of(session);
+        if !only_broken && app.config_file_struct.portable().applies_to(&name) {
+            if let Ok(contents) = fs::read_to_string(session) {
+                warn_unportable(&name, &contents, &app.home);
+            }
+        }
+        let health = SessionHealth::check(session, &app.home).with_name(&name);
+        if health.is_healthy() {
+            if !only_broken {
+                info!("{} => ok", health.name());
+            }
+            continue;
+        }
+        broken = broken.saturating_add(1);
+        if only_broken {
+            println!("{}", health.name());
+        } else {
+            for problem in health.problems() {
+                warn!("{} => {}", health.name(), problem);
+            }
+        }
+    }
+
+    if broken == 0 {
+        info!("All {} session(s) are healthy", sessions.len());
+        Ok(())
+    } else {
+        Err(VsmRuntimeFault::SessionCheck {
+            msg: format!("{} of {} session(s) have problems", broken, sessions.len()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::harness::{manager, sandbox, FakeShell};
+
+    #[test]
+    fn test_show_unknown_session_fails() {
+        let dir = sandbox("show", &["api.vim"], true);
+        let root = dir.path();
+        let err = manager(root, &["vsm", "show", "nope"], FakeShell::default())
+            .run()
+            .expect_err("unknown session is reported");
+        assert!(err.to_string().contains("nope"));
+        manager(
+            root,
+            &["vsm", "show", "api", "--json"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("empty session is shown");
+    }
+}
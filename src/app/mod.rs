@@ -0,0 +1,931 @@
+//! Consumes all crates to create the application. if you want to know how `vsm`
+//! works, read this module, each group of sub-commands lives in its own file.
+
+mod builder;
+#[cfg(test)]
+mod harness;
+mod inspect;
+mod open;
+mod organize;
+mod remove;
+mod rewrite;
+mod transfer;
+
+pub use builder::VimSessionManagerBuilder;
+
+use crate::cli::{completion_script, write_man_pages, ArgumentName, Cli};
+use crate::config::{TomlConfigFile, VimVariant, SUPPORTED_VIM_VARIATIONS};
+use crate::error::VsmRuntimeFault;
+use crate::logger::StdoutLog;
+use crate::session::{
+    companion_of, rank_by_directory, MetadataStore, OpenHistory, SessionDir, SessionDirs,
+    SessionSort, Trash,
+};
+use crate::ui::{PickerEntry, Prompt};
+use crate::utils::{match_sessions, resolve_session_name, FilesystemManager, SessionMatch, Shell};
+use log::{debug, info, warn, LevelFilter};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Wrap the entire execution process into an application object
+#[derive(Debug)]
+pub struct VimSessionManager {
+    /// Holds an internal instance of the Command Line Interface
+    cli: Cli,
+    /// Holds an internal instance of the configure file structure
+    config_file_struct: TomlConfigFile,
+    /// Holds the File system manager to write and read configs
+    fs: FilesystemManager,
+    /// Every session storage directory, the primary one first
+    dirs: SessionDirs,
+    /// Directory holding the session metadata
+    data_dir: PathBuf,
+    /// Directory project-local sessions are discovered from
+    working_dir: PathBuf,
+    /// Home directory `~` in sessions stands for
+    home: String,
+    /// Whether the user can be prompted
+    interactive: bool,
+    /// Holds Instance of users shell
+    shell: Box<dyn Shell>,
+    /// Holds the inquire prompts
+    prompt: Box<dyn Prompt>,
+    /// Is this the programs first run
+    first_run: bool,
+}
+
+impl Default for VimSessionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl VimSessionManager {
+    /// Builds the application from the process arguments and environment,
+    /// and installs the global stdout logger.
+    #[must_use]
+    pub fn new() -> Self {
+        let cli = Cli::new();
+        // Set up the stdout logger eagerly before main components
+        // are loaded so we can have logging.
+        let level_filter: LevelFilter = if *cli.debug_mode() {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        };
+        StdoutLog::init(level_filter);
+
+        VimSessionManagerBuilder::default().assemble(cli)
+    }
+
+    /// Starts building an application with explicit dependencies, nothing
+    /// that is not given is read from the process. The builder does not
+    /// install a logger.
+    #[must_use]
+    pub fn builder() -> VimSessionManagerBuilder {
+        VimSessionManagerBuilder::default()
+    }
+
+    /// Run the application. All errors that are propagated to this level are
+    /// considered un-recoverable.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault variations.
+    pub fn run(&mut self) -> Result<(), VsmRuntimeFault> {
+        // Shell completion is served before setup, it must never prompt.
+        if self.cli.completions() {
+            return self.completions();
+        }
+        if self.cli.complete() {
+            return self.complete();
+        }
+        if self.cli.generate_man() {
+            return self.generate_man();
+        }
+        self.setup()?;
+        self.subcommand_dispatcher()?;
+        Ok(())
+    }
+
+    /// Perform the application set up process, calls all otherwise setup
+    /// functions
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault.
+    fn setup(&mut self) -> Result<(), VsmRuntimeFault> {
+        // Set up disk configuration
+        if self.fs.config_file_exists() {
+            // The config file was found on disk, we load it into the config struct
+            self.first_run = false;
+            self.config_file_struct = self.fs.read_config()?;
+            self.dirs = self.dirs.clone().configure(
+                &self.config_file_struct,
+                &self.home,
+                &self.working_dir,
+            );
+        } else {
+            // This is the first run of the program, so prompt the user.
+            warn!("No config file detected");
+            self.select_vim_variation()?
+        }
+        Ok(())
+    }
+
+    /// Allows the user to select their preferred variant of vim, writes the
+    /// users selection to disk to be used for all other sessions. Called if
+    /// there is no configuration file found, or if the user calls the update
+    /// sub-command.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault.
+    fn select_vim_variation(&mut self) -> Result<(), VsmRuntimeFault> {
+        let mut installed_variations: Vec<String> = vec![];
+        let mut variants_not_installed_error_msg = String::new();
+        for variant in SUPPORTED_VIM_VARIATIONS.keys() {
+            if self.shell.is_installed(variant) {
+                installed_variations.push((*variant).to_owned());
+            } else {
+                // build a nice error string in the case that installed_variations is empty
+                variants_not_installed_error_msg.push_str(format!("{}, ", variant).as_str());
+            }
+        }
+
+        // The user does not have any of the supported variants of vim installed or in the $PATH
+        if installed_variations.is_empty() {
+            return Err(VsmRuntimeFault::NoSupportedVimVariantFound {
+                msg: variants_not_installed_error_msg,
+            });
+        }
+
+        // If this isn't the first run of the program, show the user their current
+        // active vim variant.
+        if !self.first_run {
+            info!(
+                "Current active variant is => {}",
+                self.config_file_struct.vim_variant().active_variant()
+            );
+        }
+
+        // Show the prompt of available vim variants for user selection.
+        // Update the config struct and save it to disk.
+        match self.prompt.vim_variant(installed_variations) {
+            Ok(choice) => {
+                // Small optimization, if the user selects the same variant as they already
+                // have, we won't bother updating and serializing the new selection to disk.
+                if self.first_run
+                    || *self.config_file_struct.vim_variant().active_variant() != choice
+                {
+                    let shell_command = SUPPORTED_VIM_VARIATIONS
+                        .get(choice.as_str())
+                        .expect("Failed to retrieve shell_command value from Lazy loaded hashmap");
+                    self.config_file_struct
+                        .set_vim_variant(VimVariant::new(choice, String::from(*shell_command)));
+                    self.fs.write_config(&self.config_file_struct)?;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Executes the proper code based on which sub-command was used
+    fn subcommand_dispatcher(&mut self) -> Result<(), VsmRuntimeFault> {
+        if self.cli.create() {
+            // A new session does not need any existing ones
+            return open::create(self);
+        }
+        if self.cli.restore() {
+            return remove::restore(self);
+        }
+        if self.cli.trash() {
+            return remove::trash(self);
+        }
+        if self.cli.import() {
+            // Importing into an empty session directory is fine
+            return transfer::import(self);
+        }
+        if !self.cli.variant() {
+            if self.cli.open() {
+                self.reach_last_project()?;
+            }
+            match self.dirs.load() {
+                Ok(Some(found)) => {
+                    let sessions = self.in_scope(found);
+                    if self.cli.list() {
+                        inspect::list(self, &sessions)?;
+                    }
+                    if self.cli.open() {
+                        open::open(self, &sessions)?;
+                    }
+                    if self.cli.show() {
+                        inspect::show(self, &sessions)?;
+                    }
+                    if self.cli.check() {
+                        inspect::check(self, &sessions)?;
+                    }
+                    if self.cli.rename() {
+                        organize::rename(self, &sessions)?;
+                    }
+                    if self.cli.describe() || self.cli.note() {
+                        organize::annotate(self, &sessions)?;
+                    }
+                    if self.cli.tag() {
+                        organize::tag(self, &sessions)?;
+                    }
+                    if self.cli.pin() || self.cli.unpin() {
+                        organize::pin(self, &sessions)?;
+                    }
+                    if self.cli.remove() {
+                        remove::remove(self, &sessions)?;
+                    }
+                    if self.cli.prune() {
+                        remove::prune(self, &sessions)?;
+                    }
+                    if self.cli.export() {
+                        transfer::export(self, &sessions)?;
+                    }
+                    if self.cli.relocate() {
+                        rewrite::relocate(self, &sessions)?;
+                    }
+                    if self.cli.portable() {
+                        rewrite::portable(self, &sessions)?;
+                    }
+                }
+                Ok(None) => {
+                    // Names and patterns that match nothing fail, as they do
+                    // when there are sessions
+                    let named = self.cli.active_command().arg().values();
+                    if !named.is_empty() {
+                        return Err(VsmRuntimeFault::SessionResolution {
+                            msg: format!(
+                                "No session files found, nothing matches {}",
+                                named.join(" ")
+                            ),
+                        });
+                    }
+                    warn!("No session files found");
+                    if self.cli.list() {
+                        // Machine readable formats still print an empty listing
+                        inspect::list(self, &Vec::new())?;
+                    }
+                }
+                Err(e) => {
+                    return Err(VsmRuntimeFault::SessionFileRead {
+                        msg: format!("Reading the session directories => {}", e),
+                    })
+                }
+            }
+        } else if !self.first_run {
+            self.variant()?;
+        }
+        Ok(())
+    }
+
+    /// The sessions the active sub-command works on. Project sessions are
+    /// read wherever vsm runs, so only the sub-commands reading sessions see
+    /// all of them. The others only see the project sessions named exactly
+    /// on the command line, a glob, `--all` or `prune` never reach into a
+    /// project.
+    fn in_scope(&self, sessions: Vec<PathBuf>) -> Vec<PathBuf> {
+        if self.cli.list() || self.cli.open() || self.cli.show() || self.cli.check() {
+            return sessions;
+        }
+        let arg = self.cli.active_command().arg();
+        let named = |session: &Path| {
+            let name = self.name_of(session);
+            arg.value().as_deref() == Some(name.as_str()) || arg.values().contains(&name)
+        };
+        sessions
+            .into_iter()
+            .filter(|session| !self.dirs.is_project_local(session) || named(session))
+            .collect()
+    }
+
+    /// Lets `open --last` reach the project session opened last from
+    /// anywhere, its project joins the session directories when it was not
+    /// discovered from the working directory.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::History if the history can not be read.
+    fn reach_last_project(&mut self) -> Result<(), VsmRuntimeFault> {
+        let arg = self.cli.active_command().arg();
+        if !arg.flag(ArgumentName::LAST) && arg.value().as_deref() != Some("-") {
+            return Ok(());
+        }
+        let history = self.history()?;
+        let project = history
+            .last()
+            .map(Path::new)
+            .filter(|last| last.is_absolute() && last.is_file())
+            .and_then(Path::parent);
+        if let Some(project) = project {
+            debug!("Opening the last project => {}", project.display());
+            self.dirs = self
+                .dirs
+                .clone()
+                .with_dirs([SessionDir::for_project(project)]);
+        }
+        Ok(())
+    }
+
+    /// The name of a session, prefixed by the label of its directory unless
+    /// it is in the primary one.
+    fn name_of(&self, session: &Path) -> String {
+        self.dirs.name_of(session)
+    }
+
+    /// The key of a session in the metadata and the history, see
+    /// `SessionDirs::key_of`.
+    fn key_of(&self, session: &Path) -> String {
+        self.dirs.key_of(session)
+    }
+
+    /// Reads the session metadata store.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::Metadata if the store can not be read.
+    fn metadata(&self) -> Result<MetadataStore, VsmRuntimeFault> {
+        MetadataStore::load_from_dir(&self.data_dir)
+    }
+
+    /// Reads the open history.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::History if the history can not be read.
+    fn history(&self) -> Result<OpenHistory, VsmRuntimeFault> {
+        OpenHistory::load_from_dir(&self.data_dir)
+    }
+
+    /// Sorts the sessions in the order given with `--sort`, alphabetically
+    /// if none was.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::History if the history can not be read.
+    fn sorted(&self, sessions: &[PathBuf]) -> Result<Vec<PathBuf>, VsmRuntimeFault> {
+        let sort = match self.cli.active_command().arg().option(ArgumentName::SORT) {
+            Some(sort) => sort.parse::<SessionSort>()?,
+            None => SessionSort::default(),
+        };
+        let mut sorted = sessions.to_vec();
+        sort.sort(&mut sorted, &self.dirs, &self.history()?, now());
+        Ok(sorted)
+    }
+
+    /// Builds the picker entries of the sessions, with their descriptions and
+    /// tags. The project-local sessions of the working directory are marked,
+    /// so they are offered first.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::Metadata if the store can not be read.
+    fn picker_entries(&self, sessions: &[PathBuf]) -> Result<Vec<PickerEntry>, VsmRuntimeFault> {
+        let metadata = self.metadata()?;
+        let dirs = &self.dirs;
+        Ok(sessions
+            .iter()
+            .map(|session| {
+                let local = dirs
+                    .source(session)
+                    .is_some_and(|dir| *dir.project() && self.working_dir.starts_with(dir.path()));
+                PickerEntry::new(&dirs.name_of(session), metadata.get(&dirs.key_of(session)))
+                    .with_local(local)
+            })
+            .collect())
+    }
+
+    /// Picks the session named on the command line, or prompts for one if no
+    /// name was given. `-` and `--last` pick the session opened last.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionResolution if the name matched nothing,
+    ///       or no session was opened yet.
+    ///     - VsmRuntimeFault::SelectionFailure if the prompt failed.
+    fn choose_session<'s>(&self, sessions: &'s [PathBuf]) -> Result<&'s PathBuf, VsmRuntimeFault> {
+        let arg = self.cli.active_command().arg();
+        let choice = match arg.value().as_deref() {
+            _ if arg.flag(ArgumentName::LAST) => self.last_session(sessions)?,
+            Some("-") => self.last_session(sessions)?,
+            Some(query) => self.resolve_session(query, sessions)?,
+            None => {
+                // The session working closest to the working directory is highlighted
+                let sorted = self.sorted(sessions)?;
+                let suggested = self.closest_sessions(&sorted).into_iter().next();
+                let entries: Vec<PickerEntry> = self
+                    .picker_entries(&sorted)?
+                    .into_iter()
+                    .zip(&sorted)
+                    .map(|(entry, session)| {
+                        entry.with_suggested(suggested.as_ref() == Some(session))
+                    })
+                    .collect();
+                self.prompt.session_open(&entries)?
+            }
+        };
+        sessions
+            .iter()
+            .find(|session| self.name_of(session) == choice)
+            .ok_or_else(|| VsmRuntimeFault::SessionResolution {
+                msg: format!("No session matches '{}'", choice),
+            })
+    }
+
+    /// The sessions whose working directory matches the working directory of
+    /// vsm most closely, all of them equally close.
+    fn closest_sessions(&self, sessions: &[PathBuf]) -> Vec<PathBuf> {
+        let ranked = rank_by_directory(sessions, &self.working_dir, &self.home);
+        let best = ranked.first().map(|&(_, closeness)| closeness);
+        ranked
+            .into_iter()
+            .filter(|&(_, closeness)| Some(closeness) == best)
+            .map(|(session, _)| session)
+            .collect()
+    }
+
+    /// Picks the session working closest to the working directory, see
+    /// `vsm open --here`. Equally close sessions are offered in the prompt, or
+    /// reported when there is no terminal to prompt on.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionResolution if no session works in or
+    ///       around the working directory, or several match equally well
+    ///       without a terminal to prompt on.
+    ///     - VsmRuntimeFault::SelectionFailure if the prompt failed.
+    fn session_here<'s>(&self, sessions: &'s [PathBuf]) -> Result<&'s PathBuf, VsmRuntimeFault> {
+        let closest = self.closest_sessions(sessions);
+        let choice = match *closest.as_slice() {
+            [] => {
+                return Err(VsmRuntimeFault::SessionResolution {
+                    msg: format!("No session works in {}", self.working_dir.display()),
+                })
+            }
+            [ref single] => self.name_of(single),
+            _ if self.interactive => self
+                .prompt
+                .session_open(&self.picker_entries(&self.sorted(&closest)?)?)?,
+            _ => {
+                return Err(VsmRuntimeFault::SessionResolution {
+                    msg: format!(
+                        "Several sessions work in {}, candidates are:\n{}",
+                        self.working_dir.display(),
+                        self.dirs.names(&closest).join("\n")
+                    ),
+                })
+            }
+        };
+        debug!("Closest to {} => {}", self.working_dir.display(), choice);
+        sessions
+            .iter()
+            .find(|session| self.name_of(session) == choice)
+            .ok_or_else(|| VsmRuntimeFault::SessionResolution {
+                msg: format!("No session matches '{}'", choice),
+            })
+    }
+
+    /// The name of the session that was opened last.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionResolution if no session was opened yet,
+    ///       or it is gone.
+    ///     - VsmRuntimeFault::History if the history can not be read.
+    fn last_session(&self, sessions: &[PathBuf]) -> Result<String, VsmRuntimeFault> {
+        let history = self.history()?;
+        let last = history
+            .last()
+            .ok_or_else(|| VsmRuntimeFault::SessionResolution {
+                msg: "No session was opened yet".to_owned(),
+            })?;
+        sessions
+            .iter()
+            .find(|session| self.key_of(session) == last)
+            .map(|session| self.name_of(session))
+            .ok_or_else(|| VsmRuntimeFault::SessionResolution {
+                msg: format!("The session opened last, '{}', is gone", last),
+            })
+    }
+
+    /// Resolves a session name given on the command line to a single session.
+    /// If the name is ambiguous the user is prompted with the candidates, or
+    /// when there is no terminal to prompt on, the candidates are reported.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionResolution if nothing, or more than one
+    ///       session matched without a terminal to prompt on.
+    fn resolve_session(
+        &self,
+        query: &str,
+        sessions: &[PathBuf],
+    ) -> Result<String, VsmRuntimeFault> {
+        match resolve_session_name(query, &self.dirs.names(sessions)) {
+            SessionMatch::Found(name) => {
+                debug!("Resolved '{}' => {}", query, name);
+                Ok(name)
+            }
+            SessionMatch::Ambiguous(candidates) => {
+                if self.interactive {
+                    let narrowed: Vec<PathBuf> = sessions
+                        .iter()
+                        .filter(|session| candidates.contains(&self.name_of(session)))
+                        .cloned()
+                        .collect();
+                    self.prompt
+                        .session_open(&self.picker_entries(&self.sorted(&narrowed)?)?)
+                } else {
+                    Err(VsmRuntimeFault::SessionResolution {
+                        msg: format!(
+                            "'{}' is ambiguous, candidates are:\n{}",
+                            query,
+                            candidates.join("\n")
+                        ),
+                    })
+                }
+            }
+            SessionMatch::NotFound => Err(VsmRuntimeFault::SessionResolution {
+                msg: format!("No session matches '{}'", query),
+            }),
+        }
+    }
+
+    /// The sessions matching the names or globs given to the active
+    /// sub-command, or every session with `--all`.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionResolution if a pattern matched nothing.
+    fn selected_sessions<'s>(
+        &self,
+        sessions: &'s [PathBuf],
+    ) -> Result<Vec<&'s PathBuf>, VsmRuntimeFault> {
+        let arg = self.cli.active_command().arg();
+        if arg.flag(ArgumentName::ALL) {
+            return Ok(sessions.iter().collect());
+        }
+        let names = self.dirs.names(sessions);
+        let mut selected: Vec<String> = vec![];
+        for pattern in arg.values() {
+            let matched = match_sessions(pattern, &names)?;
+            if matched.is_empty() {
+                return Err(VsmRuntimeFault::SessionResolution {
+                    msg: format!("No session matches {}", pattern),
+                });
+            }
+            selected.extend(matched);
+        }
+        Ok(sessions
+            .iter()
+            .filter(|session| selected.contains(&self.name_of(session)))
+            .collect())
+    }
+
+    /// Moves sessions into the trash along with their metadata and history,
+    /// or deletes them for good with `--permanent`.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::Trash if a session can not be trashed.
+    ///     - VsmRuntimeFault::SessionFileRemoval if a file can not be removed.
+    ///     - VsmRuntimeFault::Metadata if the metadata can not be written.
+    ///     - VsmRuntimeFault::History if the history can not be written.
+    fn delete_sessions(
+        &self,
+        targets: &[&PathBuf],
+        metadata: &mut MetadataStore,
+    ) -> Result<(), VsmRuntimeFault> {
+        let permanent = self
+            .cli
+            .active_command()
+            .arg()
+            .flag(ArgumentName::PERMANENT);
+        let trash = Trash::in_data_dir(&self.data_dir);
+        let mut history = self.history()?;
+        for session in targets {
+            let name = self.name_of(session);
+            let key = self.key_of(session);
+            if permanent {
+                info!("Removing => {}", name);
+                if let Some(companion) = companion_of(session) {
+                    self.fs.remove_file(&companion)?;
+                }
+                self.fs.remove_file(session)?;
+            } else {
+                info!("Trashing => {}", name);
+                trash.put(
+                    session,
+                    &name,
+                    metadata.get(&key).cloned(),
+                    history.get(&key).cloned(),
+                    now(),
+                )?;
+            }
+            metadata.remove(&key);
+            history.remove(&key);
+        }
+        metadata.save()?;
+        history.save()?;
+        if !permanent {
+            info!("Undo with => vsm restore <name>");
+        }
+        Ok(())
+    }
+
+    /// Asks the user to confirm the removal of sessions, unless they already
+    /// did so with `--yes`.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::SessionFileRemoval if there is no terminal to ask on.
+    fn confirm_removal(&self, count: usize, assume_yes: bool) -> Result<bool, VsmRuntimeFault> {
+        if assume_yes {
+            return Ok(true);
+        }
+        if !self.interactive {
+            return Err(VsmRuntimeFault::SessionFileRemoval {
+                msg: format!(
+                    "Refusing to remove {} session(s) without confirmation, pass --yes",
+                    count
+                ),
+            });
+        }
+        let confirmed = self
+            .prompt
+            .confirm(&format!("Remove {} session(s)?", count))?;
+        if !confirmed {
+            info!("Nothing was removed");
+        }
+        Ok(confirmed)
+    }
+
+    /// Executes sub-command completions
+    fn completions(&self) -> Result<(), VsmRuntimeFault> {
+        if let Some(shell) = self.cli.active_command().arg().value().as_deref() {
+            print!("{}", completion_script(shell)?);
+        }
+        Ok(())
+    }
+
+    /// Executes the hidden sub-command __complete, prints every session name
+    /// on its own line for the completion scripts to offer.
+    fn complete(&mut self) -> Result<(), VsmRuntimeFault> {
+        // Setup is skipped, but the configured session directories are offered
+        if let Ok(config) = self.fs.read_config() {
+            self.dirs = self
+                .dirs
+                .clone()
+                .configure(&config, &self.home, &self.working_dir);
+        }
+        match self.dirs.load() {
+            Ok(maybe_empty_sessions) => {
+                let sessions = maybe_empty_sessions.unwrap_or_default();
+                for name in self.dirs.names(&sessions) {
+                    println!("{}", name);
+                }
+                Ok(())
+            }
+            Err(e) => Err(VsmRuntimeFault::SessionFileRead { msg: e.to_string() }),
+        }
+    }
+
+    /// Executes the hidden sub-command generate-man
+    fn generate_man(&self) -> Result<(), VsmRuntimeFault> {
+        if let Some(dir) = self.cli.active_command().arg().value().as_deref() {
+            for page in write_man_pages(Path::new(dir))? {
+                info!("Wrote => {}", page.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes sub-command update
+    fn variant(&mut self) -> Result<(), VsmRuntimeFault> {
+        debug!("Updating users vim variant selection");
+        self.select_vim_variation()?;
+        info!("Succesfully updated the active vim variant");
+        Ok(())
+    }
+}
+
+/// Current time, in seconds since the unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{manager, run_in, sandbox, FakeShell};
+    use crate::error::VsmRuntimeFault;
+    use crate::session::{MetadataStore, OpenHistory};
+
+    #[test]
+    fn test_first_run_writes_selected_variant() {
+        let dir = sandbox("first-run", &[], false);
+        let root = dir.path();
+        let shell = FakeShell {
+            installed: vec!["vim".to_owned(), "nvim".to_owned()],
+            ..FakeShell::default()
+        };
+        manager(root, &["vsm", "list"], shell)
+            .run()
+            .expect("first run succeeds");
+        let config =
+            fs::read_to_string(root.join("config").join("config.toml")).expect("config is written");
+        assert!(config.contains("active_variant = \"nvim\""));
+    }
+
+    #[test]
+    fn test_first_run_without_any_variant_fails() {
+        let dir = sandbox("no-variant", &[], false);
+        let root = dir.path();
+        let result = manager(root, &["vsm", "list"], FakeShell::default()).run();
+        assert!(matches!(
+            result,
+            Err(VsmRuntimeFault::NoSupportedVimVariantFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_configured_session_dirs_keep_equal_names_apart() {
+        let dir = sandbox("dirs", &["api.vim"], true);
+        let root = dir.path();
+        let team = root.join("team");
+        fs::create_dir_all(&team).expect("team dir is created");
+        fs::write(team.join("api.vim"), "").expect("session is written");
+        let mut config =
+            fs::read_to_string(root.join("config").join("config.toml")).expect("config is read");
+        config.push_str(&format!(
+            "\n[[session_dirs]]\nlabel = \"team\"\npath = \"{}\"\n",
+            team.display()
+        ));
+        fs::write(root.join("config").join("config.toml"), config).expect("config is written");
+
+        let shell = FakeShell::default();
+        let opened = Rc::clone(&shell.opened);
+        manager(root, &["vsm", "open", "team:api"], shell)
+            .run()
+            .expect("labelled session is opened");
+        manager(
+            root,
+            &["vsm", "describe", "team:api", "shared"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("labelled session is described");
+        assert_eq!(
+            *opened.borrow(),
+            vec![format!("vim {}", team.join("api.vim").display())]
+        );
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(store.get("api").is_none());
+        assert!(store.get("team:api").is_some());
+    }
+
+    #[test]
+    fn test_grouped_sessions_open_move_and_remove() {
+        let dir = sandbox("groups", &["zsh.vim"], true);
+        let root = dir.path();
+        fs::create_dir_all(root.join("sessions").join("work")).expect("group is created");
+        fs::write(root.join("sessions").join("work").join("api.vim"), "")
+            .expect("session is written");
+
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        let commands: [&[&str]; 3] = [
+            &["vsm", "open", "work/api"],
+            &["vsm", "describe", "work/api", "backend"],
+            &["vsm", "rename", "work/api", "oss/api"],
+        ];
+        for args in commands {
+            let shell = FakeShell {
+                installed: vec![],
+                opened: Rc::clone(&opened),
+                ..FakeShell::default()
+            };
+            manager(root, args, shell)
+                .run()
+                .expect("grouped session is used");
+        }
+        assert_eq!(
+            *opened.borrow(),
+            vec![format!(
+                "vim {}",
+                root.join("sessions").join("work").join("api.vim").display()
+            )]
+        );
+        let moved = root.join("sessions").join("oss").join("api.vim");
+        assert!(moved.is_file());
+        assert!(!root.join("sessions").join("work").join("api.vim").exists());
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(store.get("work/api").is_none());
+        assert!(store.get("oss/api").is_some());
+
+        manager(
+            root,
+            &["vsm", "remove", "--yes", "oss/*"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("grouped sessions are removed");
+        assert!(!moved.exists());
+        manager(root, &["vsm", "restore", "oss/api"], FakeShell::default())
+            .run()
+            .expect("grouped session is restored");
+        assert!(moved.is_file());
+
+        // Without sub-directory levels left to scan the group is not found
+        fs::create_dir_all(root.join("sessions").join("work")).expect("group is created");
+        fs::write(root.join("sessions").join("work").join("web.vim"), "")
+            .expect("session is written");
+        let config =
+            fs::read_to_string(root.join("config").join("config.toml")).expect("config is read");
+        fs::write(
+            root.join("config").join("config.toml"),
+            format!("scan_depth = 0\n{}", config),
+        )
+        .expect("config is written");
+        manager(root, &["vsm", "open", "work/web"], FakeShell::default())
+            .run()
+            .expect_err("group is beyond the scan depth");
+    }
+
+    #[test]
+    fn test_project_sessions_are_discovered_from_the_working_dir() {
+        let dir = sandbox("projects", &["zsh.vim"], true);
+        let root = dir.path();
+        let repo = root.join("code").join("api");
+        fs::create_dir_all(repo.join(".git")).expect("repository is created");
+        fs::create_dir_all(repo.join("src")).expect("project is created");
+        fs::write(repo.join("Session.vim"), "").expect("session is written");
+
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        run_in(
+            root,
+            &["vsm", "open", "api:Session"],
+            &repo.join("src"),
+            &opened,
+        )
+        .expect("project session is opened");
+        run_in(root, &["vsm", "open", "api:Session"], root, &opened)
+            .expect_err("project session is only found inside the project");
+        assert_eq!(
+            *opened.borrow(),
+            vec![format!("vim {}", repo.join("Session.vim").display())]
+        );
+
+        let config =
+            fs::read_to_string(root.join("config").join("config.toml")).expect("config is read");
+        fs::write(
+            root.join("config").join("config.toml"),
+            format!(
+                "{}\n[projects]\ndiscover = false\nworkspaces = [\"{}\"]\n",
+                config,
+                root.join("code").display()
+            ),
+        )
+        .expect("config is written");
+        run_in(root, &["vsm", "open", "api:Session"], root, &opened)
+            .expect("workspace projects are discovered");
+    }
+
+    #[test]
+    fn test_project_sessions_are_keyed_by_path_and_kept_out_of_globs() {
+        let dir = sandbox("project-scope", &["zsh.vim"], true);
+        let root = dir.path();
+        let repo = root.join("code").join("api");
+        fs::create_dir_all(repo.join(".git")).expect("repository is created");
+        fs::write(repo.join("Session.vim"), "").expect("session is written");
+        let key = fs::canonicalize(repo.join("Session.vim"))
+            .expect("session is canonical")
+            .to_string_lossy()
+            .to_string();
+
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        run_in(
+            root,
+            &["vsm", "describe", "api:Session", "REST"],
+            &repo,
+            &opened,
+        )
+        .expect("a named project session is described");
+        run_in(root, &["vsm", "remove", "*", "--yes"], &repo, &opened)
+            .expect("the sessions directory is emptied");
+        assert!(repo.join("Session.vim").exists());
+        assert!(!root.join("sessions").join("zsh.vim").exists());
+        run_in(root, &["vsm", "open", "api:Session"], &repo, &opened)
+            .expect("project session is opened");
+
+        let metadata = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert_eq!(
+            metadata.get(&key).and_then(|m| m.description().clone()),
+            Some("REST".to_owned())
+        );
+        let history = OpenHistory::load_from_dir(&root.join("data")).expect("history is read");
+        assert_eq!(history.last(), Some(key.as_str()));
+
+        run_in(root, &["vsm", "open", "--last"], root, &opened)
+            .expect("the last project session is opened from anywhere");
+        assert_eq!(
+            *opened.borrow(),
+            vec![format!("vim {}", repo.join("Session.vim").display()); 2]
+        );
+    }
+}
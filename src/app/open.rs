@@ -0,0 +1,302 @@
+//! Opens existing sessions and records new ones.
+
+use super::{now, VimSessionManager};
+use crate::cli::ArgumentName;
+use crate::error::VsmRuntimeFault;
+use crate::session::{
+    autosave_commands, editor_arguments, open_portable, save_on_exit_commands, to_portable,
+};
+use log::{debug, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Executes sub-command open, and records the open in the history. A
+/// portable session is opened with its paths expanded for the home
+/// directory, see `open_portable`.
+///
+/// # Errors
+///     - VsmRuntimeFault::CommandExecutor if the editor can not be spawned.
+///     - VsmRuntimeFault::Relocate if a portable session can not be
+///       rewritten.
+///     - VsmRuntimeFault::History if the history can not be updated.
+pub(super) fn open(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Opening a session");
+    let session = if app.cli.active_command().arg().flag(ArgumentName::HERE) {
+        app.session_here(sessions)?
+    } else {
+        app.choose_session(sessions)?
+    };
+    let autosave = app.config_file_struct.autosave();
+    let name = app.name_of(session);
+    let commands = if autosave.applies_to(&name) {
+        debug!("Autosaving {}", name);
+        autosave_commands(session, *autosave.interval(), *autosave.buf_enter())
+    } else {
+        vec![]
+    };
+    let launch = || {
+        app.shell.open_editor_with_session(
+            app.config_file_struct.vim_variant().active_variant(),
+            app.config_file_struct.vim_variant().shell_command(),
+            &session.to_string_lossy(),
+            &commands,
+        )
+    };
+    let launched = if app.config_file_struct.portable().applies_to(&name) {
+        let (launched, saved) = open_portable(session, &app.home, launch)?;
+        if saved {
+            debug!("Made {} portable", name);
+        }
+        launched
+    } else {
+        launch()
+    };
+    let exit_status = launched?;
+    debug!("Editor exited with {:?}", exit_status);
+    let mut history = app.history()?;
+    history.record(&app.key_of(session), now(), exit_status);
+    history.save()
+}
+
+/// Executes sub-command new. The editor is told to write the session
+/// when it exits, so nothing is written if it fails to start. With
+/// `--force` a session that already has the name is moved to the trash,
+/// along with its metadata and history, before the editor starts.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionName if the name is invalid.
+///     - VsmRuntimeFault::SessionCreation if the session exists without
+///       `--force`, or the working directory does not exist.
+///     - VsmRuntimeFault::Trash if the existing session can not be trashed.
+///     - VsmRuntimeFault::CommandExecutor if the editor can not be spawned.
+///     - VsmRuntimeFault::Relocate if the session should be portable, but
+///       can not be rewritten.
+pub(super) fn create(app: &VimSessionManager) -> Result<(), VsmRuntimeFault> {
+    debug!("Creating a session");
+    let arg = app.cli.active_command().arg();
+    let session = app
+        .dirs
+        .path_for(arg.option(ArgumentName::NAME).map_or("", String::as_str))?;
+    let name = app.name_of(&session);
+    if session.exists() && !arg.flag(ArgumentName::FORCE) {
+        return Err(VsmRuntimeFault::SessionCreation {
+            msg: format!("'{}' already exists, pass --force to overwrite it", name),
+        });
+    }
+    let cwd = arg.option(ArgumentName::CWD).map(PathBuf::from);
+    if let Some(dir) = cwd.as_deref() {
+        if !dir.is_dir() {
+            return Err(VsmRuntimeFault::SessionCreation {
+                msg: format!("{} is not a directory", dir.display()),
+            });
+        }
+    }
+    let mut metadata = app.metadata()?;
+    if session.exists() {
+        // The session in the way goes to the trash instead of being lost
+        app.delete_sessions(&[&session], &mut metadata)?;
+    }
+    let session_dir = session.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(session_dir).map_err(|e| VsmRuntimeFault::SessionCreation {
+        msg: format!("{} => {}", session_dir.display(), e),
+    })?;
+
+    let arguments = editor_arguments(
+        app.config_file_struct.vim_variant().shell_command(),
+        &save_on_exit_commands(&session),
+        arg.values(),
+    );
+    app.shell.launch_editor(
+        app.config_file_struct.vim_variant().active_variant(),
+        &arguments,
+        cwd.as_deref(),
+    )?;
+    if session.exists() {
+        if app.config_file_struct.portable().applies_to(&name) {
+            to_portable(&app.home).apply_to(&session)?;
+        }
+        let created_at = now();
+        let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_owned());
+        metadata.entry(&app.key_of(&session)).set_created(
+            &user,
+            app.config_file_struct.vim_variant().active_variant(),
+            created_at,
+        );
+        metadata.save()?;
+        info!("Saved => {}", session.display());
+    } else {
+        warn!("The editor exited without writing {}", session.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{manager, sandbox, FakeShell};
+    use crate::session::{MetadataStore, OpenHistory, Trash};
+
+    #[test]
+    fn test_open_by_name_launches_editor() {
+        let dir = sandbox("open", &["api.vim", "dotfiles.vim"], true);
+        let root = dir.path();
+        let shell = FakeShell::default();
+        let opened = Rc::clone(&shell.opened);
+        manager(root, &["vsm", "open", "dot"], shell)
+            .run()
+            .expect("session is opened");
+        let expected = format!(
+            "vim {}",
+            root.join("sessions").join("dotfiles.vim").display()
+        );
+        assert_eq!(*opened.borrow(), vec![expected]);
+    }
+
+    #[test]
+    fn test_open_here_picks_the_closest_working_directory() {
+        let dir = sandbox("here", &[], true);
+        let root = dir.path();
+        let sessions = root.join("sessions");
+        let cds = [
+            ("api", root.join("code").join("api")),
+            ("home", root.to_path_buf()),
+            ("web", PathBuf::from("/nowhere")),
+        ];
+        for (name, cd) in cds {
+            fs::write(
+                sessions.join(format!("{}.vim", name)),
+                format!("cd {}\n", cd.display()),
+            )
+            .expect("session is written");
+        }
+
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        for _ in 0..2 {
+            let shell = FakeShell {
+                installed: vec![],
+                opened: Rc::clone(&opened),
+                ..FakeShell::default()
+            };
+            manager(root, &["vsm", "open", "--here"], shell)
+                .run()
+                .expect("closest session is opened");
+            // The exact match is gone, the session working below is next
+            if sessions.join("home.vim").exists() {
+                fs::remove_file(sessions.join("home.vim")).expect("session is removed");
+            }
+        }
+        assert_eq!(
+            *opened.borrow(),
+            vec![
+                format!("vim {}", sessions.join("home.vim").display()),
+                format!("vim {}", sessions.join("api.vim").display()),
+            ]
+        );
+
+        fs::remove_file(sessions.join("api.vim")).expect("session is removed");
+        manager(root, &["vsm", "open", "--here"], FakeShell::default())
+            .run()
+            .expect_err("no session works here");
+    }
+
+    #[test]
+    fn test_open_last_reopens_previous_session() {
+        let dir = sandbox("open-last", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        manager(root, &["vsm", "open", "-"], FakeShell::default())
+            .run()
+            .expect_err("nothing was opened yet");
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        let commands: [&[&str]; 3] = [
+            &["vsm", "open", "web"],
+            &["vsm", "open", "--last"],
+            &["vsm", "open", "-"],
+        ];
+        for args in commands {
+            let shell = FakeShell {
+                installed: vec![],
+                opened: Rc::clone(&opened),
+                ..FakeShell::default()
+            };
+            manager(root, args, shell).run().expect("session is opened");
+        }
+        let web = format!("vim {}", root.join("sessions").join("web.vim").display());
+        assert_eq!(*opened.borrow(), vec![web.clone(), web.clone(), web]);
+        let history = OpenHistory::load_from_dir(&root.join("data")).expect("history is read");
+        assert_eq!(history.get("web").map(|r| *r.count()), Some(3));
+        assert_eq!(history.get("web").and_then(|r| *r.exit_status()), Some(0));
+    }
+
+    #[test]
+    fn test_new_launches_editor_that_saves_on_exit() {
+        let dir = sandbox("new", &["api.vim"], true);
+        let root = dir.path();
+        let shell = FakeShell::default();
+        let opened = Rc::clone(&shell.opened);
+        manager(root, &["vsm", "new", "web", "index.html"], shell)
+            .run()
+            .expect("editor is started");
+        let launched = opened.borrow().join("\n");
+        assert!(launched.starts_with("vim -c let v:this_session = "));
+        assert!(launched.contains("VimLeavePre"));
+        assert!(launched.ends_with("web.vim') index.html"));
+
+        manager(root, &["vsm", "new", "api"], FakeShell::default())
+            .run()
+            .expect_err("existing session is not overwritten");
+
+        manager(
+            root,
+            &["vsm", "describe", "api", "REST gateway"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("session is described");
+        manager(
+            root,
+            &["vsm", "new", "api", "--force"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("editor is started");
+        assert!(!root.join("sessions").join("api.vim").exists());
+        let trashed = Trash::in_data_dir(&root.join("data"))
+            .entries()
+            .expect("trash is read");
+        assert_eq!(trashed.len(), 1);
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(store.get("api").is_none());
+    }
+
+    #[test]
+    fn test_open_injects_autosave_when_opted_in() {
+        let dir = sandbox("autosave", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        fs::write(
+            root.join("config").join("config.toml"),
+            "[vim_variant]\nactive_variant = \"vim\"\nshell_command = \"-S\"\n\n\
+             [autosave]\nsessions = [\"api\"]\n",
+        )
+        .expect("config is written");
+        let opened: Rc<RefCell<Vec<String>>> = Rc::default();
+        for name in ["api", "web"] {
+            let shell = FakeShell {
+                installed: vec![],
+                opened: Rc::clone(&opened),
+                ..FakeShell::default()
+            };
+            manager(root, &["vsm", "open", name], shell)
+                .run()
+                .expect("session is opened");
+        }
+        let launched = opened.borrow();
+        assert!(launched.first().is_some_and(|l| l.contains("VimLeavePre")));
+        assert!(launched.get(1).is_some_and(|l| !l.contains(" -c ")));
+    }
+}
@@ -0,0 +1,269 @@
+//! Renames sessions and edits their metadata.
+
+use super::VimSessionManager;
+use crate::cli::{ArgumentName, SubCommandName};
+use crate::error::VsmRuntimeFault;
+use crate::session::move_session;
+use log::{debug, info, warn};
+use std::path::PathBuf;
+
+/// Executes sub-command rename, prompting for whatever was not given on
+/// the command line. A grouped name, such as `work/api`, moves the
+/// session into that group. With `--force` a session that already has the
+/// new name is moved to the trash.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionName if the new name is invalid.
+///     - VsmRuntimeFault::SessionRename if the new name is taken
+///       without `--force`, or the files can not be moved.
+///     - VsmRuntimeFault::Trash if the session in the way can not be trashed.
+///     - VsmRuntimeFault::Metadata if the metadata can not be moved along.
+///     - VsmRuntimeFault::History if the history can not be moved along.
+pub(super) fn rename(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Renaming a session");
+    let arg = app.cli.active_command().arg();
+    let session = app.choose_session(sessions)?;
+    let old_name = app.name_of(session);
+    let old_key = app.key_of(session);
+    let new_name = match arg.option(ArgumentName::NEW_NAME) {
+        Some(name) => name.clone(),
+        None => app
+            .prompt
+            .text("What should the session be called?", &old_name)?,
+    };
+    // A grouped or labelled name moves the session to that group or directory
+    let target = app.dirs.path_for(&new_name)?;
+    let mut metadata = app.metadata()?;
+    if arg.flag(ArgumentName::FORCE) && target.exists() && target != *session {
+        // The session in the way goes to the trash instead of being lost
+        app.delete_sessions(&[&target], &mut metadata)?;
+    }
+    let renamed = move_session(session, &target)?;
+    metadata.rename(&old_key, &app.key_of(&renamed));
+    metadata.save()?;
+    let mut history = app.history()?;
+    history.rename(&old_key, &app.key_of(&renamed));
+    history.save()?;
+    info!("Renamed {} => {}", old_name, renamed.display());
+    Ok(())
+}
+
+/// Executes sub-commands describe and note. Without any text the current
+/// value is edited in a prompt, or printed when there is no terminal.
+///
+/// # Errors
+///     - VsmRuntimeFault::Metadata if the store can not be read or written.
+///     - VsmRuntimeFault::SelectionFailure if the prompt failed.
+pub(super) fn annotate(
+    app: &VimSessionManager,
+    sessions: &[PathBuf],
+) -> Result<(), VsmRuntimeFault> {
+    let describe = app.cli.describe();
+    debug!("Annotating a session");
+    let session = app.choose_session(sessions)?;
+    let name = app.name_of(session);
+    let key = app.key_of(session);
+    let mut metadata = app.metadata()?;
+    let current = metadata
+        .get(&key)
+        .and_then(|m| {
+            if describe {
+                m.description().clone()
+            } else {
+                m.note().clone()
+            }
+        })
+        .unwrap_or_default();
+
+    let text = match app.cli.active_command().arg().option(ArgumentName::TEXT) {
+        Some(text) => text.clone(),
+        None if app.interactive => {
+            let message = if describe {
+                format!("Describe {}", name)
+            } else {
+                format!("Note for {}", name)
+            };
+            app.prompt.text(&message, &current)?
+        }
+        None => {
+            if !current.is_empty() {
+                println!("{}", current);
+            }
+            return Ok(());
+        }
+    };
+    let entry = metadata.entry(&key);
+    if describe {
+        entry.set_description(&text);
+    } else {
+        entry.set_note(&text);
+    }
+    metadata.save()?;
+    info!("Updated {}", name);
+    Ok(())
+}
+
+/// Executes sub-command tag, adding or removing the given tags.
+///
+/// # Errors
+///     - VsmRuntimeFault::Metadata if the store can not be read or written.
+pub(super) fn tag(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Tagging a session");
+    let arg = app.cli.active_command().arg();
+    let add = arg
+        .option(ArgumentName::ACTION)
+        .is_some_and(|action| action == SubCommandName::TAG_ADD);
+    let session = app.choose_session(sessions)?;
+    let name = app.name_of(session);
+    let key = app.key_of(session);
+    let mut metadata = app.metadata()?;
+    let entry = metadata.entry(&key);
+    for tag in arg
+        .option(ArgumentName::TAGS)
+        .map(|tags| tags.split_whitespace().collect::<Vec<&str>>())
+        .unwrap_or_default()
+    {
+        let changed = if add {
+            entry.add_tag(tag)
+        } else {
+            entry.remove_tag(tag)
+        };
+        if !changed {
+            warn!(
+                "{} {} tag {}",
+                name,
+                if add { "already has" } else { "has no" },
+                tag
+            );
+        }
+    }
+    metadata.save()?;
+    info!(
+        "Tags of {} => {}",
+        name,
+        metadata
+            .get(&key)
+            .map(|m| m.tags().join(", "))
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Executes sub-commands pin and unpin.
+///
+/// # Errors
+///     - VsmRuntimeFault::Metadata if the store can not be read or written.
+pub(super) fn pin(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    let pin = app.cli.pin();
+    debug!("Pinning a session");
+    let session = app.choose_session(sessions)?;
+    let name = app.name_of(session);
+    let key = app.key_of(session);
+    let mut metadata = app.metadata()?;
+    if metadata.entry(&key).set_pinned(pin) {
+        metadata.save()?;
+        info!("{} {}", if pin { "Pinned" } else { "Unpinned" }, name);
+    } else {
+        info!(
+            "{} is {} already",
+            name,
+            if pin { "pinned" } else { "not pinned" }
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{manager, sandbox, FakeShell};
+    use crate::session::{MetadataStore, Trash};
+
+    #[test]
+    fn test_rename_refuses_overwrite_without_force() {
+        let dir = sandbox("rename", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        manager(root, &["vsm", "rename", "api", "web"], FakeShell::default())
+            .run()
+            .expect_err("existing session is not overwritten");
+        manager(
+            root,
+            &["vsm", "rename", "api", "web", "--force"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("session is renamed");
+        assert!(!root.join("sessions").join("api.vim").exists());
+        assert!(root.join("sessions").join("web.vim").exists());
+    }
+
+    #[test]
+    fn test_forced_rename_trashes_the_session_in_the_way() {
+        let dir = sandbox("rename-force", &[], true);
+        let root = dir.path();
+        let sessions = root.join("sessions");
+        // bx.vim is a session of its own, not the companion of b.vim
+        for name in ["a", "b", "bx"] {
+            fs::write(
+                sessions.join(format!("{}.vim", name)),
+                format!("let SessionLoad = 1\n\" {}\n", name),
+            )
+            .expect("session is written");
+        }
+        manager(
+            root,
+            &["vsm", "rename", "a", "b", "--force"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("session is renamed");
+
+        assert!(!sessions.join("a.vim").exists());
+        assert_eq!(
+            fs::read_to_string(sessions.join("b.vim")).expect("session is read"),
+            "let SessionLoad = 1\n\" a\n"
+        );
+        assert!(sessions.join("bx.vim").exists());
+        let trashed = Trash::in_data_dir(&root.join("data"))
+            .entries()
+            .expect("trash is read");
+        assert_eq!(
+            trashed.iter().map(|entry| entry.name()).collect::<Vec<_>>(),
+            vec!["b"]
+        );
+    }
+
+    #[test]
+    fn test_metadata_follows_rename_and_remove() {
+        let dir = sandbox("metadata", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        let commands: [&[&str]; 3] = [
+            &["vsm", "describe", "api", "REST gateway"],
+            &["vsm", "tag", "add", "api", "#work"],
+            &["vsm", "rename", "api", "gateway"],
+        ];
+        for args in commands {
+            manager(root, args, FakeShell::default())
+                .run()
+                .expect("command succeeds");
+        }
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(store.get("api").is_none());
+        let gateway = store.get("gateway").expect("metadata followed the rename");
+        assert_eq!(gateway.description().as_deref(), Some("REST gateway"));
+        assert_eq!(gateway.tags(), &vec!["work".to_owned()]);
+
+        manager(
+            root,
+            &["vsm", "remove", "gateway", "--yes"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("session is removed");
+        let reloaded = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(reloaded.sessions().is_empty());
+    }
+}
@@ -0,0 +1,414 @@
+//! Removes sessions into the trash, and brings them back.
+
+use super::{now, VimSessionManager};
+use crate::cli::{ArgumentName, SubCommandName};
+use crate::error::VsmRuntimeFault;
+use crate::session::{companion_of, parse_age, PrunePolicy, Trash};
+use crate::ui::PickerEntry;
+use crate::utils::match_sessions;
+use log::{debug, info, warn};
+use std::path::PathBuf;
+
+/// Executes sub-command remove. Sessions are either given as names and
+/// glob patterns on the command line, or picked interactively. Pinned
+/// sessions are skipped unless `--force` is given.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionResolution if any pattern matched nothing,
+///       matched sessions are still removed.
+///     - VsmRuntimeFault::SessionFileRemoval if pinned sessions were
+///       skipped, the others are still removed.
+pub(super) fn remove(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Removing stale sessions");
+    let arg = app.cli.active_command().arg();
+    let mut unmatched: Vec<&str> = vec![];
+    let selected_sessions: Vec<String> = if arg.values().is_empty() {
+        app.prompt
+            .session_remove(&app.picker_entries(&app.sorted(sessions)?)?)?
+    } else {
+        let names = app.dirs.names(sessions);
+        let mut selected: Vec<String> = vec![];
+        for pattern in arg.values() {
+            let matched = match_sessions(pattern, &names)?;
+            if matched.is_empty() {
+                unmatched.push(pattern);
+            }
+            for name in matched {
+                if !selected.contains(&name) {
+                    selected.push(name);
+                }
+            }
+        }
+        selected
+    };
+
+    let mut metadata = app.metadata()?;
+    let force = arg.flag(ArgumentName::FORCE);
+    let mut pinned: Vec<String> = vec![];
+    let targets: Vec<&PathBuf> = sessions
+        .iter()
+        .filter(|session| selected_sessions.contains(&app.name_of(session)))
+        .filter(|session| {
+            let name = app.name_of(session);
+            let protected = !force
+                && metadata
+                    .get(&app.key_of(session))
+                    .is_some_and(|m| *m.pinned());
+            if protected {
+                warn!("{} is pinned, pass --force to remove it", name);
+                pinned.push(name);
+            }
+            !protected
+        })
+        .collect();
+
+    if arg.flag(ArgumentName::DRY_RUN) {
+        // The companion goes wherever its session goes
+        for session in &targets {
+            println!("{}", session.display());
+            if let Some(companion) = companion_of(session) {
+                println!("{}", companion.display());
+            }
+        }
+    } else if !targets.is_empty()
+        && app.confirm_removal(targets.len(), arg.flag(ArgumentName::YES))?
+    {
+        app.delete_sessions(&targets, &mut metadata)?;
+    }
+
+    if !unmatched.is_empty() {
+        return Err(VsmRuntimeFault::SessionResolution {
+            msg: format!("No session matches {}", unmatched.join(", ")),
+        });
+    }
+    if pinned.is_empty() {
+        Ok(())
+    } else {
+        Err(VsmRuntimeFault::SessionFileRemoval {
+            msg: format!("Skipped pinned session(s) {}", pinned.join(", ")),
+        })
+    }
+}
+
+/// Executes sub-command prune. The sessions the policy picks are always
+/// previewed, and only removed once confirmed.
+///
+/// # Errors
+///     - VsmRuntimeFault::Prune if no criteria were given or configured,
+///       or they are invalid.
+///     - VsmRuntimeFault::SessionFileRemoval if a session can not be
+///       removed.
+pub(super) fn prune(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Pruning stale sessions");
+    let arg = app.cli.active_command().arg();
+    let mut policy = PrunePolicy::default();
+    if let Some(age) = arg.option(ArgumentName::OLDER_THAN) {
+        let age_by = arg
+            .option(ArgumentName::AGE_BY)
+            .map(|measure| measure.parse())
+            .transpose()?
+            .unwrap_or_default();
+        policy = policy.with_older_than(parse_age(age)?, age_by);
+    }
+    if arg.flag(ArgumentName::BROKEN) {
+        policy = policy.with_broken();
+    }
+    if arg.flag(ArgumentName::NEVER_OPENED) {
+        policy = policy.with_never_opened();
+    }
+    if policy.is_empty() {
+        debug!("No criteria given, using the configured policy");
+        policy = PrunePolicy::from_config(app.config_file_struct.prune())?;
+    }
+    if policy.is_empty() {
+        return Err(VsmRuntimeFault::Prune {
+            msg: "No criteria given, pass them or set a [prune] policy in config.toml. \
+                  --keep only spares sessions, it prunes nothing on its own"
+                .to_owned(),
+        });
+    }
+    if let Some(keep) = arg.option(ArgumentName::KEEP) {
+        policy = policy.with_keep(keep.parse().map_err(|e| VsmRuntimeFault::Prune {
+            msg: format!("--keep {} => {}", keep, e),
+        })?);
+    }
+
+    let mut metadata = app.metadata()?;
+    let history = app.history()?;
+    for kept in policy.kept(sessions, &app.dirs, &history) {
+        info!("{} => kept, recently used", app.name_of(kept));
+    }
+    let candidates = policy.select(sessions, &app.dirs, &history, &metadata, &app.home, now());
+    if candidates.is_empty() {
+        info!("Nothing to prune");
+        return Ok(());
+    }
+    for candidate in &candidates {
+        info!("{} => {}", candidate.name(), candidate.reasons().join(", "));
+    }
+    if arg.flag(ArgumentName::DRY_RUN) {
+        return Ok(());
+    }
+    if app.confirm_removal(candidates.len(), arg.flag(ArgumentName::YES))? {
+        let targets: Vec<&PathBuf> = candidates.iter().map(|c| c.path()).collect();
+        app.delete_sessions(&targets, &mut metadata)?;
+    }
+    Ok(())
+}
+
+/// Executes sub-command restore, moving a session and what vsm knew
+/// about it back out of the trash.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionResolution if nothing by that name is in
+///       the trash.
+///     - VsmRuntimeFault::Trash if a session took its place without
+///       `--force`, or the files can not be moved.
+pub(super) fn restore(app: &VimSessionManager) -> Result<(), VsmRuntimeFault> {
+    debug!("Restoring a session");
+    let arg = app.cli.active_command().arg();
+    let trash = Trash::in_data_dir(&app.data_dir);
+    let entries = trash.entries()?;
+    if entries.is_empty() {
+        info!("The trash is empty");
+        return Ok(());
+    }
+    let name = match arg.value().as_deref() {
+        Some(name) => name.to_owned(),
+        None => {
+            let offered: Vec<PickerEntry> = entries
+                .iter()
+                .map(|entry| PickerEntry::new(entry.name(), entry.metadata().as_ref()))
+                .collect();
+            app.prompt.session_open(&offered)?
+        }
+    };
+    let entry = entries
+        .iter()
+        .find(|entry| *entry.name() == name)
+        .ok_or_else(|| VsmRuntimeFault::SessionResolution {
+            msg: format!("No trashed session named '{}'", name),
+        })?;
+
+    let restored = trash.restore(entry, arg.flag(ArgumentName::FORCE))?;
+    let key = app.key_of(&restored);
+    if let Some(restored_metadata) = entry.metadata().as_ref() {
+        let mut metadata = app.metadata()?;
+        metadata.insert(&key, restored_metadata.clone());
+        metadata.save()?;
+    }
+    if let Some(record) = entry.history().as_ref() {
+        let mut history = app.history()?;
+        history.insert(&key, record.clone());
+        history.save()?;
+    }
+    info!("Restored => {}", restored.display());
+    Ok(())
+}
+
+/// Executes sub-commands trash list and trash empty.
+///
+/// # Errors
+///     - VsmRuntimeFault::Trash if the trash can not be read or emptied.
+///     - VsmRuntimeFault::Prune if the age is invalid.
+pub(super) fn trash(app: &VimSessionManager) -> Result<(), VsmRuntimeFault> {
+    let arg = app.cli.active_command().arg();
+    let trash = Trash::in_data_dir(&app.data_dir);
+    let entries = trash.entries()?;
+    let now = now();
+    let days_ago = |deleted_at: u64| now.saturating_sub(deleted_at).checked_div(86_400);
+
+    if arg
+        .option(ArgumentName::ACTION)
+        .is_some_and(|action| action == SubCommandName::TRASH_LIST)
+    {
+        debug!("Listing the trash");
+        if entries.is_empty() {
+            info!("The trash is empty");
+        }
+        for entry in &entries {
+            info!(
+                "{}  trashed {} day(s) ago  {}",
+                entry.name(),
+                days_ago(*entry.deleted_at()).unwrap_or_default(),
+                entry.original().display()
+            );
+        }
+        return Ok(());
+    }
+
+    debug!("Emptying the trash");
+    let older_than = arg
+        .option(ArgumentName::OLDER_THAN)
+        .map(|age| parse_age(age))
+        .transpose()?
+        .unwrap_or_default();
+    let expired: Vec<_> = entries
+        .iter()
+        .filter(|entry| now.saturating_sub(*entry.deleted_at()) >= older_than)
+        .collect();
+    if expired.is_empty() {
+        info!("Nothing to delete");
+        return Ok(());
+    }
+    if app.confirm_removal(expired.len(), arg.flag(ArgumentName::YES))? {
+        for entry in expired {
+            info!("Deleting => {}", entry.name());
+            trash.purge(entry)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{builder, manager, sandbox, FakeShell};
+    use crate::error::VsmRuntimeFault;
+    use crate::session::MetadataStore;
+
+    #[test]
+    fn test_remove_glob_with_yes() {
+        let dir = sandbox("remove", &["api.vim", "tmp-1.vim", "tmp-2.vim"], true);
+        let root = dir.path();
+        manager(
+            root,
+            &["vsm", "remove", "tmp-*", "--yes"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("sessions are removed");
+        let mut left: Vec<String> = fs::read_dir(root.join("sessions"))
+            .expect("sessions are readable")
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["api.vim".to_owned()]);
+    }
+
+    #[test]
+    fn test_remove_skips_pinned_without_force() {
+        let dir = sandbox("remove-pinned", &["api.vim", "tmp-1.vim"], true);
+        let root = dir.path();
+        manager(root, &["vsm", "pin", "api"], FakeShell::default())
+            .run()
+            .expect("session is pinned");
+        let result = manager(root, &["vsm", "remove", "*", "--yes"], FakeShell::default()).run();
+        assert!(matches!(
+            result,
+            Err(VsmRuntimeFault::SessionFileRemoval { .. })
+        ));
+        assert!(root.join("sessions").join("api.vim").exists());
+        assert!(!root.join("sessions").join("tmp-1.vim").exists());
+        manager(
+            root,
+            &["vsm", "remove", "api", "--yes", "--force"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("pinned session is removed with --force");
+        assert!(!root.join("sessions").join("api.vim").exists());
+    }
+
+    #[test]
+    fn test_prune_previews_and_applies_configured_policy() {
+        let dir = sandbox("prune", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        manager(root, &["vsm", "prune", "--yes"], FakeShell::default())
+            .run()
+            .expect_err("nothing to prune by");
+        manager(root, &["vsm", "open", "api"], FakeShell::default())
+            .run()
+            .expect("session is opened");
+        manager(
+            root,
+            &["vsm", "prune", "--never-opened", "--dry-run"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("prune is previewed");
+        assert!(root.join("sessions").join("web.vim").exists());
+
+        fs::write(
+            root.join("config").join("config.toml"),
+            "[vim_variant]\nactive_variant = \"vim\"\nshell_command = \"-S\"\n\n\
+             [prune]\nnever_opened = true\n",
+        )
+        .expect("config is written");
+        manager(root, &["vsm", "prune", "--yes"], FakeShell::default())
+            .run()
+            .expect("configured policy is applied");
+        assert!(root.join("sessions").join("api.vim").exists());
+        assert!(!root.join("sessions").join("web.vim").exists());
+    }
+
+    #[test]
+    fn test_remove_trashes_and_restore_brings_back() {
+        let dir = sandbox("trash", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        let commands: [&[&str]; 3] = [
+            &["vsm", "describe", "api", "REST gateway"],
+            &["vsm", "remove", "api", "--yes"],
+            &["vsm", "remove", "web", "--yes", "--permanent"],
+        ];
+        for args in commands {
+            manager(root, args, FakeShell::default())
+                .run()
+                .expect("command succeeds");
+        }
+        assert!(!root.join("sessions").join("api.vim").exists());
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert!(store.get("api").is_none());
+
+        manager(root, &["vsm", "restore", "web"], FakeShell::default())
+            .run()
+            .expect_err("deleted sessions are not in the trash");
+        manager(root, &["vsm", "restore", "api"], FakeShell::default())
+            .run()
+            .expect("session is restored");
+        assert!(root.join("sessions").join("api.vim").exists());
+        assert!(!root.join("sessions").join("web.vim").exists());
+        let restored = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert_eq!(
+            restored.get("api").and_then(|m| m.description().clone()),
+            Some("REST gateway".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_remove_unmatched_pattern_fails() {
+        let dir = sandbox("remove-unmatched", &["api.vim"], true);
+        let root = dir.path();
+        let result = manager(
+            root,
+            &["vsm", "remove", "nope", "--yes"],
+            FakeShell::default(),
+        )
+        .run();
+        assert!(matches!(
+            result,
+            Err(VsmRuntimeFault::SessionResolution { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_asks_the_prompt_to_confirm() {
+        let dir = sandbox("remove-confirm", &["api.vim"], true);
+        let root = dir.path();
+        manager(root, &["vsm", "remove", "api"], FakeShell::default())
+            .run()
+            .expect_err("there is no terminal to confirm on");
+        // The prompt declines
+        builder(root, &["vsm", "remove", "api"], FakeShell::default())
+            .interactive(true)
+            .build()
+            .expect("arguments are valid")
+            .run()
+            .expect("removal is declined");
+        assert!(root.join("sessions").join("api.vim").exists());
+    }
+}
@@ -0,0 +1,281 @@
+//! Rewrites the paths written in sessions.
+
+use super::{now, VimSessionManager};
+use crate::cli::ArgumentName;
+use crate::error::VsmRuntimeFault;
+use crate::session::{backup_session, to_portable, unportable_paths, LineChange, Relocation};
+use log::{debug, info, warn};
+use std::fs;
+use std::path::PathBuf;
+
+/// Executes sub-command relocate. The changed lines of every session are
+/// previewed as a diff, and the sessions are only rewritten once
+/// confirmed, each after a backup was taken.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionResolution if a pattern matched nothing.
+///     - VsmRuntimeFault::SessionFileRead if a session can not be read.
+///     - VsmRuntimeFault::Relocate if the directories are invalid, the
+///       rewrite was not confirmed without a terminal, or a session can
+///       not be backed up or written.
+pub(super) fn relocate(
+    app: &VimSessionManager,
+    sessions: &[PathBuf],
+) -> Result<(), VsmRuntimeFault> {
+    debug!("Relocating sessions");
+    let arg = app.cli.active_command().arg();
+    let relocation = Relocation::new(
+        arg.option(ArgumentName::FROM).map_or("", String::as_str),
+        arg.option(ArgumentName::TO).map_or("", String::as_str),
+        &app.home,
+    )?;
+    let mut targets: Vec<(&PathBuf, String, String)> = vec![];
+    for session in app.selected_sessions(sessions)? {
+        let name = app.name_of(session);
+        let contents =
+            fs::read_to_string(session).map_err(|e| VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", session.display(), e),
+            })?;
+        let changes = relocation.changes(&contents);
+        if changes.is_empty() {
+            continue;
+        }
+        print_changes(&name, &changes);
+        targets.push((session, name, relocation.rewrite(&contents)));
+    }
+    if targets.is_empty() {
+        info!("No session refers to {}", relocation.from());
+        return Ok(());
+    }
+    if arg.flag(ArgumentName::DRY_RUN) {
+        return Ok(());
+    }
+    if !arg.flag(ArgumentName::YES) {
+        if !app.interactive {
+            return Err(VsmRuntimeFault::Relocate {
+                msg: format!(
+                    "Refusing to rewrite {} session(s) without confirmation, pass --yes",
+                    targets.len()
+                ),
+            });
+        }
+        if !app
+            .prompt
+            .confirm(&format!("Rewrite {} session(s)?", targets.len()))?
+        {
+            info!("Nothing was rewritten");
+            return Ok(());
+        }
+    }
+
+    for (session, name, rewritten) in targets {
+        let backup = backup_session(session, &name, &app.data_dir, now())?;
+        fs::write(session, rewritten).map_err(|e| VsmRuntimeFault::Relocate {
+            msg: format!("{} => {}", session.display(), e),
+        })?;
+        info!("Relocated => {} (backup {})", name, backup.display());
+    }
+    Ok(())
+}
+
+/// Executes sub-command portable. The paths under the home directory are
+/// spelled with `~` after the changes were previewed and a backup was
+/// taken, paths that stay absolute are warned about.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionResolution if a pattern matched nothing.
+///     - VsmRuntimeFault::SessionFileRead if a session can not be read.
+///     - VsmRuntimeFault::Relocate if a session can not be backed up or
+///       written.
+pub(super) fn portable(
+    app: &VimSessionManager,
+    sessions: &[PathBuf],
+) -> Result<(), VsmRuntimeFault> {
+    debug!("Making sessions portable");
+    let dry_run = app.cli.active_command().arg().flag(ArgumentName::DRY_RUN);
+    let portable = to_portable(&app.home);
+    for session in app.selected_sessions(sessions)? {
+        let name = app.name_of(session);
+        let contents =
+            fs::read_to_string(session).map_err(|e| VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", session.display(), e),
+            })?;
+        warn_unportable(&name, &contents, &app.home);
+        let changes = portable.changes(&contents);
+        if changes.is_empty() {
+            info!("{} => already portable", name);
+            continue;
+        }
+        print_changes(&name, &changes);
+        if dry_run {
+            continue;
+        }
+        let backup = backup_session(session, &name, &app.data_dir, now())?;
+        portable.apply_to(session)?;
+        info!("Made portable => {} (backup {})", name, backup.display());
+    }
+    Ok(())
+}
+
+/// Prints the lines of a session a rewrite changes as a unified diff.
+pub(super) fn print_changes(name: &str, changes: &[LineChange]) {
+    println!("--- {}", name);
+    println!("+++ {}", name);
+    for change in changes {
+        println!("@@ -{} +{} @@", change.number(), change.number());
+        println!("-{}", change.before());
+        println!("+{}", change.after());
+    }
+}
+
+/// Warns about the paths of a session that stay absolute when it is made
+/// portable.
+///
+/// # Arguments
+///     - name Name of the session.
+///     - contents The vim script written by `:mksession`.
+///     - home The home directory portable paths are spelled relative to.
+pub(super) fn warn_unportable(name: &str, contents: &str, home: &str) {
+    for path in unportable_paths(contents, home) {
+        warn!(
+            "{} => line {}: {} is outside of $HOME and is not portable",
+            name,
+            path.number(),
+            path.path()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{manager, sandbox, FakeShell};
+
+    #[test]
+    fn test_relocate_previews_then_rewrites_with_a_backup() {
+        let dir = sandbox("relocate", &["zsh.vim"], true);
+        let root = dir.path();
+        let old = root.join("src").join("x");
+        let new = root.join("code").join("x");
+        let session = root.join("sessions").join("api.vim");
+        let original = format!("cd {}\nbadd +1 {}/main.rs\n", old.display(), old.display());
+        fs::write(&session, &original).expect("session is written");
+        let (from, to) = (old.to_string_lossy(), new.to_string_lossy());
+
+        let commands: [&[&str]; 2] = [
+            &["vsm", "relocate", "--dry-run", &from, &to, "api"],
+            &["vsm", "relocate", "-n", "--all", &from, &to],
+        ];
+        for args in commands {
+            manager(root, args, FakeShell::default())
+                .run()
+                .expect("changes are previewed");
+        }
+        assert_eq!(
+            fs::read_to_string(&session).expect("session is read"),
+            original
+        );
+        manager(
+            root,
+            &["vsm", "relocate", &from, &to, "api"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect_err("rewriting needs confirmation");
+
+        manager(
+            root,
+            &["vsm", "relocate", "--yes", "--all", &from, &to],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("sessions are relocated");
+        assert_eq!(
+            fs::read_to_string(&session).expect("session is read"),
+            format!("cd {}\nbadd +1 {}/main.rs\n", new.display(), new.display())
+        );
+        let backups: Vec<PathBuf> = fs::read_dir(root.join("data").join("backups"))
+            .expect("backups are written")
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(
+            backups
+                .first()
+                .map(|backup| fs::read_to_string(backup).expect("backup is read")),
+            Some(original)
+        );
+    }
+
+    #[test]
+    fn test_portable_sessions_spell_home_with_a_tilde() {
+        let dir = sandbox("portable", &["zsh.vim"], true);
+        let root = dir.path();
+        let home = root.join("home").to_string_lossy().to_string();
+        let sessions = root.join("sessions");
+        fs::write(
+            sessions.join("api.vim"),
+            format!("cd {}/code/api\nbadd +1 /srv/api/notes.md\n", home),
+        )
+        .expect("session is written");
+        fs::write(sessions.join("web.vim"), "cd ~/code/web\n").expect("session is written");
+
+        manager(
+            root,
+            &["vsm", "portable", "--dry-run", "api"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("changes are previewed");
+        assert!(fs::read_to_string(sessions.join("api.vim"))
+            .expect("session is read")
+            .starts_with(&format!("cd {}/", home)));
+        manager(root, &["vsm", "portable", "api"], FakeShell::default())
+            .run()
+            .expect("session is made portable");
+        assert_eq!(
+            fs::read_to_string(sessions.join("api.vim")).expect("session is read"),
+            "cd ~/code/api\nbadd +1 /srv/api/notes.md\n"
+        );
+        assert!(root.join("data").join("backups").is_dir());
+
+        // Opened sessions are expanded for the editor only, what it saves is
+        // made portable
+        let config =
+            fs::read_to_string(root.join("config").join("config.toml")).expect("config is read");
+        fs::write(
+            root.join("config").join("config.toml"),
+            format!("{}\n[portable]\nsessions = [\"web\"]\n", config),
+        )
+        .expect("config is written");
+        let opening = FakeShell::default();
+        let sourced = Rc::clone(&opening.sourced);
+        manager(root, &["vsm", "open", "web"], opening)
+            .run()
+            .expect("session is opened");
+        assert_eq!(*sourced.borrow(), vec![format!("cd {}/code/web\n", home)]);
+        assert_eq!(
+            fs::read_to_string(sessions.join("web.vim")).expect("session is read"),
+            "cd ~/code/web\n"
+        );
+        let shell = FakeShell {
+            saves: Some(format!(
+                "cd {0}/code/web\nbadd +1 {0}/code/web/a.rs\n",
+                home
+            )),
+            ..FakeShell::default()
+        };
+        manager(root, &["vsm", "open", "web"], shell)
+            .run()
+            .expect("session is opened");
+        assert_eq!(
+            fs::read_to_string(sessions.join("web.vim")).expect("session is read"),
+            "cd ~/code/web\nbadd +1 ~/code/web/a.rs\n"
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Moves sessions between machines as bundles.
+
+use super::{now, VimSessionManager};
+use crate::cli::ArgumentName;
+use crate::error::VsmRuntimeFault;
+use crate::session::{
+    companion_of, companion_path, free_name, BundledSession, ClashPolicy, MetadataStore,
+    SessionBundle, LABEL_SEPARATOR,
+};
+use crate::utils::match_sessions;
+use log::{debug, info, warn};
+use std::path::{Path, PathBuf};
+
+/// Executes sub-command export, every session is exported when no
+/// patterns are given.
+///
+/// # Errors
+///     - VsmRuntimeFault::SessionResolution if a pattern matched nothing.
+///     - VsmRuntimeFault::Bundle if the bundle can not be written.
+pub(super) fn export(app: &VimSessionManager, sessions: &[PathBuf]) -> Result<(), VsmRuntimeFault> {
+    debug!("Exporting sessions");
+    let arg = app.cli.active_command().arg();
+    let archive = arg.option(ArgumentName::ARCHIVE).map_or("", String::as_str);
+    let names = app.dirs.names(sessions);
+    let mut selected: Vec<String> = vec![];
+    for pattern in arg.values() {
+        let matched = match_sessions(pattern, &names)?;
+        if matched.is_empty() {
+            return Err(VsmRuntimeFault::SessionResolution {
+                msg: format!("No session matches {}", pattern),
+            });
+        }
+        selected.extend(matched);
+    }
+    let targets: Vec<PathBuf> = sessions
+        .iter()
+        .filter(|session| arg.values().is_empty() || selected.contains(&app.name_of(session)))
+        .cloned()
+        .collect();
+
+    let manifest = SessionBundle::export(
+        Path::new(archive),
+        &targets,
+        &app.dirs,
+        &app.metadata()?,
+        &app.home,
+        now(),
+    )?;
+    for session in manifest.sessions() {
+        info!("Exported => {}", session.name());
+    }
+    info!(
+        "Wrote {} session(s) => {}",
+        manifest.sessions().len(),
+        archive
+    );
+    Ok(())
+}
+
+/// Executes sub-command import. Sessions whose name is taken are handled
+/// by `--on-clash`, or the user is asked for each of them. A session that
+/// is overwritten is moved to the trash first. Every name is settled before
+/// anything is written, so a clash that can not be resolved imports nothing.
+///
+/// # Errors
+///     - VsmRuntimeFault::Bundle if the bundle can not be read, does not
+///       match its manifest, a file is in the way of an overwritten session,
+///       or a session can not be written.
+///     - VsmRuntimeFault::SessionName if a session has no valid name here.
+///     - VsmRuntimeFault::Trash if an overwritten session can not be trashed.
+///     - VsmRuntimeFault::Metadata if the metadata can not be written.
+pub(super) fn import(app: &VimSessionManager) -> Result<(), VsmRuntimeFault> {
+    debug!("Importing sessions");
+    let arg = app.cli.active_command().arg();
+    let archive = arg.option(ArgumentName::ARCHIVE).map_or("", String::as_str);
+    let policy = arg
+        .option(ArgumentName::ON_CLASH)
+        .map(|policy| policy.parse::<ClashPolicy>())
+        .transpose()?;
+    let dry_run = arg.flag(ArgumentName::DRY_RUN);
+    let bundle = SessionBundle::read(Path::new(archive))?;
+    let dirs = &app.dirs;
+    let home = &app.home;
+    let source_home = bundle.manifest().source_home();
+    let rewrite_home = arg.flag(ArgumentName::REWRITE_HOME);
+    if !rewrite_home && source_home != home {
+        warn!(
+            "Exported from {}, pass --rewrite-home to point its paths at {}",
+            source_home, home
+        );
+    }
+
+    let mut planned: Vec<(&BundledSession, String, PathBuf)> = vec![];
+    for bundled in bundle.manifest().sessions() {
+        let taken = |candidate: &str| {
+            dirs.path_for(candidate)
+                .is_ok_and(|path| is_taken(&path, bundled))
+                || planned
+                    .iter()
+                    .any(|planned_session| planned_session.1 == candidate)
+        };
+        let wanted = import_name(app, bundled.name());
+        let name = if taken(&wanted) {
+            match import_clash(app, &wanted, policy, dry_run, &taken)? {
+                Some(name) => name,
+                None => {
+                    info!("Skipped => {}", bundled.name());
+                    continue;
+                }
+            }
+        } else {
+            wanted
+        };
+        let target = dirs.path_for(&name)?;
+        // Trashing the session in the way only takes its own companion along
+        let companion = companion_path(&target);
+        if bundled.companion_sha256().is_some()
+            && companion.exists()
+            && companion_of(&target).is_none()
+        {
+            return Err(VsmRuntimeFault::Bundle {
+                msg: format!(
+                    "{} is in the way of the companion of '{}'",
+                    companion.display(),
+                    name
+                ),
+            });
+        }
+        if dry_run {
+            info!("{} => {}", bundled.name(), target.display());
+        }
+        planned.push((bundled, name, target));
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let mut metadata = app.metadata()?;
+    let mut installed = Ok(());
+    let local_home = rewrite_home.then_some(home.as_str());
+    for &(bundled, _, ref target) in &planned {
+        installed = install(app, &bundle, bundled, target, local_home, &mut metadata);
+        if installed.is_err() {
+            break;
+        }
+    }
+    // The sessions imported before a failure keep their metadata
+    metadata.save()?;
+    installed
+}
+
+/// Returns true if importing a session to a path would write over a file.
+///
+/// # Arguments
+///     - target Path the session would be imported to.
+///     - bundled The bundled session, its companion is written next to it.
+fn is_taken(target: &Path, bundled: &BundledSession) -> bool {
+    target.exists() || (bundled.companion_sha256().is_some() && companion_path(target).exists())
+}
+
+/// Writes a bundled session, trashing the session in its way first.
+///
+/// # Arguments
+///     - home The local home directory paths are rewritten to, if any.
+///
+/// # Errors
+///     - VsmRuntimeFault::Trash if an overwritten session can not be trashed.
+///     - VsmRuntimeFault::Bundle if the session can not be written.
+fn install(
+    app: &VimSessionManager,
+    bundle: &SessionBundle,
+    bundled: &BundledSession,
+    target: &PathBuf,
+    home: Option<&str>,
+    metadata: &mut MetadataStore,
+) -> Result<(), VsmRuntimeFault> {
+    if target.exists() {
+        app.delete_sessions(&[target], metadata)?;
+    }
+    let session = bundle.install(bundled, target, home)?;
+    match bundled.metadata().as_ref() {
+        Some(bundled_metadata) => metadata.insert(&app.key_of(&session), bundled_metadata.clone()),
+        None => metadata.remove(&app.key_of(&session)),
+    }
+    info!("Imported => {}", session.display());
+    Ok(())
+}
+
+/// The name a bundled session is imported as before any clash is handled.
+/// A session from a session directory whose label is not configured here
+/// goes to the primary directory instead.
+fn import_name(app: &VimSessionManager, name: &str) -> String {
+    match name.split_once(LABEL_SEPARATOR) {
+        Some((label, stem)) if app.dirs.path_for(name).is_err() => {
+            warn!(
+                "No session directory is labelled '{}', importing {} as {}",
+                label, name, stem
+            );
+            stem.to_owned()
+        }
+        _ => name.to_owned(),
+    }
+}
+
+/// Decides what happens to an imported session whose name is taken.
+///
+/// # Returns
+///     - The name to import the session as, None to skip it.
+///
+/// # Errors
+///     - VsmRuntimeFault::SelectionFailure if a prompt fails.
+///     - VsmRuntimeFault::SessionName if a chosen name is invalid.
+fn import_clash(
+    app: &VimSessionManager,
+    name: &str,
+    policy: Option<ClashPolicy>,
+    dry_run: bool,
+    taken: &dyn Fn(&str) -> bool,
+) -> Result<Option<String>, VsmRuntimeFault> {
+    let ask = policy.is_none() && app.interactive && !dry_run;
+    let chosen = match policy {
+        Some(policy) => policy,
+        None if ask => app
+            .prompt
+            .choose(
+                &format!(
+                    "'{}' already exists, what should happen to the import?",
+                    name
+                ),
+                &ClashPolicy::NAMES,
+            )?
+            .parse()?,
+        None => {
+            warn!("'{}' already exists, pass --on-clash to import it", name);
+            ClashPolicy::Skip
+        }
+    };
+    match chosen {
+        ClashPolicy::Rename => {
+            let suggested = free_name(name, taken);
+            let renamed = if ask {
+                app.prompt.text("Import it as?", &suggested)?
+            } else {
+                suggested
+            };
+            app.dirs.path_for(&renamed)?;
+            Ok(Some(renamed))
+        }
+        ClashPolicy::Skip => Ok(None),
+        ClashPolicy::Overwrite => Ok(Some(name.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::app::harness::{manager, sandbox, FakeShell};
+    use crate::session::{MetadataStore, Trash};
+
+    #[test]
+    fn test_export_then_import_handles_clashes() {
+        let dir = sandbox("bundle", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        let sessions = root.join("sessions");
+        fs::create_dir_all(sessions.join("work")).expect("group is created");
+        fs::write(sessions.join("work").join("api.vim"), "").expect("session is written");
+        let archive = root.join("bundle.tar");
+        let archive_arg = archive.to_string_lossy().to_string();
+        let commands: [&[&str]; 5] = [
+            &["vsm", "describe", "api", "REST gateway"],
+            &["vsm", "describe", "work/api", "Work gateway"],
+            &["vsm", "export", &archive_arg, "api", "work/api"],
+            &["vsm", "import", &archive_arg, "--on-clash", "rename"],
+            &["vsm", "import", &archive_arg, "--on-clash", "skip"],
+        ];
+        for args in commands {
+            manager(root, args, FakeShell::default())
+                .run()
+                .expect("command succeeds");
+        }
+        assert!(sessions.join("api-2.vim").exists());
+        assert!(sessions.join("work").join("api-2.vim").exists());
+        assert!(!sessions.join("api-3.vim").exists());
+        assert!(!sessions.join("web-2.vim").exists());
+        let store = MetadataStore::load_from_dir(&root.join("data")).expect("store is read");
+        assert_eq!(
+            store.get("api-2").and_then(|m| m.description().clone()),
+            Some("REST gateway".to_owned())
+        );
+        assert_eq!(
+            store
+                .get("work/api-2")
+                .and_then(|m| m.description().clone()),
+            Some("Work gateway".to_owned())
+        );
+
+        manager(
+            root,
+            &["vsm", "import", &archive_arg, "--on-clash", "overwrite"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("sessions are overwritten");
+        let trashed = Trash::in_data_dir(&root.join("data"))
+            .entries()
+            .expect("trash is read");
+        assert_eq!(trashed.len(), 2);
+
+        manager(
+            root,
+            &["vsm", "export", &archive_arg, "nope"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect_err("unmatched patterns fail");
+    }
+
+    #[test]
+    fn test_import_treats_a_companion_in_the_way_as_taken() {
+        let dir = sandbox("bundle-companion", &["api.vim", "web.vim"], true);
+        let root = dir.path();
+        let sessions = root.join("sessions");
+        fs::write(sessions.join("apix.vim"), "set nowrap\n").expect("companion is written");
+        let archive = root.join("bundle.tar");
+        let archive_arg = archive.to_string_lossy().to_string();
+        manager(
+            root,
+            &["vsm", "export", &archive_arg, "api", "web"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("sessions are exported");
+        // Only a session of its own is left where the companion would go
+        for session in ["api.vim", "apix.vim", "web.vim"] {
+            fs::remove_file(sessions.join(session)).expect("session is removed");
+        }
+        fs::write(sessions.join("apix.vim"), "let SessionLoad = 1\n").expect("session is written");
+
+        manager(
+            root,
+            &["vsm", "import", &archive_arg, "--on-clash", "overwrite"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect_err("the companion can not be written");
+        assert!(!sessions.join("api.vim").exists());
+        assert!(!sessions.join("web.vim").exists());
+
+        manager(
+            root,
+            &["vsm", "import", &archive_arg, "--on-clash", "rename"],
+            FakeShell::default(),
+        )
+        .run()
+        .expect("sessions are imported");
+        assert!(sessions.join("api-2.vim").exists());
+        assert!(sessions.join("api-2x.vim").exists());
+        assert!(sessions.join("web.vim").exists());
+        assert_eq!(
+            fs::read_to_string(sessions.join("apix.vim")).expect("session is read"),
+            "let SessionLoad = 1\n"
+        );
+    }
+}
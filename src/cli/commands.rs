@@ -1,5 +1,6 @@
 //! Sub-command declarations for the command line wrapper and helper structures
 
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result};
 
 use derive_getters::Getters;
@@ -15,8 +16,54 @@ impl<'scmd> SubCommandName {
     pub const OPEN: &'scmd str = "open";
     /// Removes a vim session
     pub const REMOVE: &'scmd str = "remove";
+    /// Shows the details of a vim session
+    pub const SHOW: &'scmd str = "show";
+    /// Validates every vim session against the filesystem
+    pub const CHECK: &'scmd str = "check";
+    /// Starts the editor for a new vim session
+    pub const NEW: &'scmd str = "new";
+    /// Renames a vim session
+    pub const RENAME: &'scmd str = "rename";
+    /// Sets or prints the description of a vim session
+    pub const DESCRIBE: &'scmd str = "describe";
+    /// Sets or prints the note of a vim session
+    pub const NOTE: &'scmd str = "note";
+    /// Adds or removes tags of a vim session
+    pub const TAG: &'scmd str = "tag";
+    /// Adds tags, nested under tag
+    pub const TAG_ADD: &'scmd str = "add";
+    /// Removes tags, nested under tag
+    pub const TAG_REMOVE: &'scmd str = "rm";
+    /// Moves a trashed vim session back
+    pub const RESTORE: &'scmd str = "restore";
+    /// Lists or empties the trash
+    pub const TRASH: &'scmd str = "trash";
+    /// Lists the trashed sessions, nested under trash
+    pub const TRASH_LIST: &'scmd str = "list";
+    /// Permanently deletes trashed sessions, nested under trash
+    pub const TRASH_EMPTY: &'scmd str = "empty";
+    /// Writes vim sessions into a bundle to carry them to another machine
+    pub const EXPORT: &'scmd str = "export";
+    /// Reads vim sessions from a bundle
+    pub const IMPORT: &'scmd str = "import";
+    /// Rewrites the paths of vim sessions after a project moved
+    pub const RELOCATE: &'scmd str = "relocate";
+    /// Spells the paths of vim sessions under the home directory with `~`
+    pub const PORTABLE: &'scmd str = "portable";
+    /// Removes stale vim sessions
+    pub const PRUNE: &'scmd str = "prune";
+    /// Pins a vim session, so it is offered first
+    pub const PIN: &'scmd str = "pin";
+    /// Unpins a vim session
+    pub const UNPIN: &'scmd str = "unpin";
     /// Changes the users vim variant selection
     pub const VARIANT: &'scmd str = "variant";
+    /// Prints a shell completion script
+    pub const COMPLETIONS: &'scmd str = "completions";
+    /// Hidden, prints the session names for the completion scripts
+    pub const COMPLETE: &'scmd str = "__complete";
+    /// Hidden, writes the man pages into a directory
+    pub const GENERATE_MAN: &'scmd str = "generate-man";
 }
 
 /// Global optional commands are defined here. Optional commands such as
@@ -24,29 +71,153 @@ impl<'scmd> SubCommandName {
 #[derive(Debug)]
 pub struct OptionalCommandName;
 
-impl<'ocmd> OptionalCommandName {
+impl OptionalCommandName {
     /// Runs the app in debug mode. used as Boolean flag
-    pub const DEBUG: &'ocmd str = "debug";
+    pub const DEBUG: &str = "debug";
+}
+
+/// Names of the arguments accepted by sub-commands are defined here
+#[derive(Debug)]
+pub struct ArgumentName;
+
+impl ArgumentName {
+    /// Name of a session file, without the `.vim` extension
+    pub const SESSION: &str = "session";
+    /// Name of a session that does not exist yet, without the `.vim` extension
+    pub const NAME: &str = "name";
+    /// Files to open
+    pub const FILES: &str = "files";
+    /// Working directory of the editor
+    pub const CWD: &str = "cwd";
+    /// New name of a session, without the `.vim` extension
+    pub const NEW_NAME: &str = "new-name";
+    /// Overwrites existing files. Used as Boolean flag
+    pub const FORCE: &str = "force";
+    /// Skips confirmation prompts. Used as Boolean flag
+    pub const YES: &str = "yes";
+    /// Free-form text, such as a description or a note
+    pub const TEXT: &str = "text";
+    /// Tags of a session
+    pub const TAGS: &str = "tags";
+    /// Which nested sub-command was used, e.g. add or rm of tag
+    pub const ACTION: &str = "action";
+    /// Deletes sessions instead of trashing them. Used as Boolean flag
+    pub const PERMANENT: &str = "permanent";
+    /// Reports what would happen without touching the disk. Used as Boolean flag
+    pub const DRY_RUN: &str = "dry-run";
+    /// Limits a listing to the pinned sessions. Used as Boolean flag
+    pub const PINNED: &str = "pinned";
+    /// Maximum age of a session, e.g. `30d`
+    pub const OLDER_THAN: &str = "older-than";
+    /// What the age of a session is measured by
+    pub const AGE_BY: &str = "age-by";
+    /// Selects sessions with missing files. Used as Boolean flag
+    pub const BROKEN: &str = "broken";
+    /// Selects sessions that were never opened. Used as Boolean flag
+    pub const NEVER_OPENED: &str = "never-opened";
+    /// Number of most recently used sessions to keep
+    pub const KEEP: &str = "keep";
+    /// Order sessions are listed and offered in
+    pub const SORT: &str = "sort";
+    /// Picks the session that was opened last. Used as Boolean flag
+    pub const LAST: &str = "last";
+    /// Picks the session working closest to the working directory. Used as
+    /// Boolean flag
+    pub const HERE: &str = "here";
+    /// Output format of machine readable listings
+    pub const FORMAT: &str = "format";
+    /// Selects JSON output. Used as Boolean flag
+    pub const JSON: &str = "json";
+    /// Limits a report to the broken sessions. Used as Boolean flag
+    pub const ONLY_BROKEN: &str = "only-broken";
+    /// Name of a supported shell
+    pub const SHELL: &str = "shell";
+    /// Path to a directory
+    pub const DIRECTORY: &str = "dir";
+    /// Path to a session bundle
+    pub const ARCHIVE: &str = "archive";
+    /// What to do with imported sessions whose name is taken
+    pub const ON_CLASH: &str = "on-clash";
+    /// Rewrites paths under the exporting `$HOME`. Used as Boolean flag
+    pub const REWRITE_HOME: &str = "rewrite-home";
+    /// Directory paths are moved from
+    pub const FROM: &str = "from";
+    /// Directory paths are moved to
+    pub const TO: &str = "to";
+    /// Selects every session. Used as Boolean flag
+    pub const ALL: &str = "all";
 }
 
 /// Helps distinguish betwixt arguments that have values, and arguments that
-/// don't. At this point in the application, no sub-commands have arguments.
-/// Argument exists to future-proof the application.
+/// don't. Sub-commands that take no arguments use the default value.
 #[derive(Debug, Clone, Default, Getters)]
 pub struct Argument {
     /// The value of the argument
     value: Option<String>,
     /// The name of the argument
     name: Option<String>,
+    /// Every value of the argument, for arguments that can be repeated
+    values: Vec<String>,
+    /// Names of the Boolean flags that were set
+    flags: Vec<String>,
+    /// Named options and the value they were given
+    options: BTreeMap<String, String>,
+}
+
+impl Argument {
+    /// Builds a new named Argument.
+    ///
+    /// # Arguments
+    ///     - name Name of the argument, see `ArgumentName`.
+    ///     - values Values given on the command line, the first one is also
+    ///       available as the single value.
+    pub fn new(name: &str, values: Vec<String>) -> Self {
+        Self {
+            value: values.first().cloned(),
+            name: Some(String::from(name)),
+            values,
+            ..Self::default()
+        }
+    }
+
+    /// Records a Boolean flag, if it was set.
+    #[must_use]
+    pub fn with_flag(mut self, flag: &str, set: bool) -> Self {
+        if set {
+            self.flags.push(String::from(flag));
+        }
+        self
+    }
+
+    /// Records a named option, if it was given a value.
+    #[must_use]
+    pub fn with_option(mut self, option: &str, value: Option<String>) -> Self {
+        if let Some(v) = value {
+            self.options.insert(String::from(option), v);
+        }
+        self
+    }
+
+    /// Returns true if the Boolean flag was set.
+    pub fn flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    /// Returns the value of a named option, if it was given.
+    pub fn option(&self, option: &str) -> Option<&String> {
+        self.options.get(option)
+    }
 }
 
 impl Display for Argument {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "Argument Name: {:?}\nValue: {:?}",
+            "Argument Name: {:?}\nValue: {:?}\nFlags: {:?}\nOptions: {:?}",
             self.name(),
-            self.value()
+            self.values(),
+            self.flags(),
+            self.options()
         )
     }
 }
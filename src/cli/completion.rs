@@ -0,0 +1,164 @@
+//! Generates shell completion scripts from the clap command tree, and wires
+//! live session name completion into them through the hidden `__complete`
+//! sub-command.
+
+use clap::Command;
+use clap_complete::{generate, Shell};
+
+use super::commands::ArgumentName;
+use super::wrapper::Cli;
+use crate::error::VsmRuntimeFault;
+
+/// Shells a completion script can be generated for.
+pub const COMPLETION_SHELLS: [&str; 4] = ["bash", "zsh", "fish", "elvish"];
+
+/// Wraps the generated bash completion function, offering session names once
+/// a sub-command which takes them has been typed.
+const BASH_HOOK: &str = r#"
+___BIN___sessions() {
+    local i word cur="${COMP_WORDS[COMP_CWORD]}"
+    for (( i = 1; i < COMP_CWORD; i++ )); do
+        word="${COMP_WORDS[i]}"
+        [[ "$word" == -* ]] && continue
+        case "$word" in
+            __COMMANDS__)
+                if [[ "$cur" != -* ]]; then
+                    COMPREPLY=( $(compgen -W "$(__BIN__ __complete 2>/dev/null)" -- "$cur") )
+                    return 0
+                fi
+                ;;
+        esac
+        break
+    done
+    ___BIN__ "$@"
+}
+
+complete -F ___BIN___sessions -o bashdefault -o default __BIN__
+"#;
+
+/// Completion function the zsh session arguments are pointed at.
+const ZSH_HOOK: &str = r#"
+(( $+functions[___BIN___sessions] )) ||
+___BIN___sessions() {
+    local -a sessions
+    sessions=(${(f)"$(__BIN__ __complete 2>/dev/null)"})
+    _describe -t sessions 'session' sessions
+}
+"#;
+
+/// Adds session names to the fish completions of the session sub-commands.
+const FISH_HOOK: &str = r#"
+complete -c __BIN__ -n "__fish_seen_subcommand_from __COMMANDS__" -f -a "(__BIN__ __complete 2>/dev/null)"
+"#;
+
+/// Wraps the generated elvish completer, offering session names once a
+/// sub-command which takes them has been typed.
+const ELVISH_HOOK: &str = r#"
+var __BIN__-static-completer = $edit:completion:arg-completer[__BIN__]
+set edit:completion:arg-completer[__BIN__] = {|@words|
+    var positional = [(for word $words[1..] { if (not (str:has-prefix $word '-')) { put $word } })]
+    if (and (> (count $positional) 1) (has-value [__COMMANDS__] $positional[0])) {
+        __BIN__ __complete
+    } else {
+        $__BIN__-static-completer $@words
+    }
+}
+"#;
+
+/// Builds the completion script for a shell.
+///
+/// # Arguments
+///     * shell Name of the shell, one of `COMPLETION_SHELLS`.
+///
+/// # Errors
+///     * VsmRuntimeFault::Completion if the shell is not supported.
+pub fn completion_script(shell: &str) -> Result<String, VsmRuntimeFault> {
+    let (target, hook, separator) = match shell {
+        "bash" => (Shell::Bash, BASH_HOOK, "|"),
+        "zsh" => (Shell::Zsh, ZSH_HOOK, " "),
+        "fish" => (Shell::Fish, FISH_HOOK, " "),
+        "elvish" => (Shell::Elvish, ELVISH_HOOK, " "),
+        _ => {
+            return Err(VsmRuntimeFault::Completion {
+                msg: format!("'{}' is not a supported shell", shell),
+            })
+        }
+    };
+    let mut command = Cli::command();
+    let bin = command.get_name().to_owned();
+    let hook = hook
+        .replace(
+            "__COMMANDS__",
+            &session_subcommands(&command).join(separator),
+        )
+        .replace("__BIN__", &bin);
+
+    let mut buffer: Vec<u8> = vec![];
+    generate(target, &mut command, bin.as_str(), &mut buffer);
+    let script = String::from_utf8_lossy(&buffer);
+
+    if target == Shell::Zsh {
+        Ok(point_zsh_sessions_at(&script, &bin, &hook))
+    } else {
+        Ok(format!("{}{}", script, hook))
+    }
+}
+
+/// Points the zsh session arguments at the session completion function, and
+/// defines it right after the `#compdef` header so it exists whether the
+/// script is sourced or autoloaded.
+fn point_zsh_sessions_at(script: &str, bin: &str, hook: &str) -> String {
+    let session_spec = format!("::{} -- ", ArgumentName::SESSION);
+    let mut patched = String::with_capacity(script.len());
+    for (idx, line) in script.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with(&format!("'{}", session_spec))
+            || trimmed.starts_with(&format!("'*{}", session_spec))
+        {
+            patched.push_str(&line.replace(":_default'", &format!(":_{}_sessions'", bin)));
+        } else {
+            patched.push_str(line);
+        }
+        patched.push('\n');
+        if idx == 0 {
+            patched.push_str(hook);
+        }
+    }
+    patched
+}
+
+/// Collects the names of the sub-commands which take session names.
+fn session_subcommands(command: &Command) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|sub| {
+            sub.get_positionals()
+                .any(|arg| arg.get_id() == ArgumentName::SESSION)
+        })
+        .map(|sub| sub.get_name().to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{completion_script, COMPLETION_SHELLS};
+
+    #[test]
+    fn test_every_shell_completes_session_names() {
+        for shell in COMPLETION_SHELLS {
+            let script = completion_script(shell).expect("supported shell");
+            assert!(script.contains("vsm __complete"), "{}", shell);
+            assert!(!script.contains("'__complete"), "{}", shell);
+        }
+    }
+
+    #[test]
+    fn test_zsh_session_arguments_use_session_completer() {
+        let script = completion_script("zsh").expect("supported shell");
+        assert!(script.starts_with("#compdef vsm\n"));
+        assert!(!script
+            .lines()
+            .any(|line| line.starts_with("'::session") && line.contains(":_default'")));
+        assert!(script.contains(":_vsm_sessions'"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Generates man pages and the long-form help reference from the clap command
+//! tree, so the documentation can never drift from the actual command line.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::Command;
+use clap_mangen::roff::{bold, roman, Roff};
+use clap_mangen::Man;
+
+use super::wrapper::Cli;
+use crate::error::VsmRuntimeFault;
+
+/// Environment variables vsm reads, and what they are used for.
+const ENVIRONMENT: [(&str, &str); 4] = [
+    (
+        "VIM_SESSIONS",
+        "Directory holding the session files, or a colon separated list of them where an \
+         entry may be labelled as label=path. New sessions go to the first one. Defaults to \
+         $HOME/.config/vim_sessions.",
+    ),
+    (
+        "SHELL",
+        "Shell used to probe for installed vim variants. Defaults to /bin/sh.",
+    ),
+    (
+        "HOME",
+        "Root of the default session and configuration paths.",
+    ),
+    (
+        "RUST_LOG",
+        "Overrides the log level, see the env_logger crate.",
+    ),
+];
+
+/// Files vsm reads or writes, and what they hold.
+const FILES: [(&str, &str); 5] = [
+    (
+        "$HOME/.config/vsm/config.toml",
+        "Holds the active vim variant, written on the first run and by the variant \
+         sub-command, the [autosave] settings, the [prune] policy, the [portable] \
+         sessions, the [projects] searched for a Session.vim, extra [[session_dirs]] \
+         and the scan_depth of their sub-directories.",
+    ),
+    (
+        "$HOME/.local/share/vsm/metadata.toml",
+        "Holds the descriptions, notes and tags of the sessions, written by the describe, \
+         note and tag sub-commands.",
+    ),
+    (
+        "$HOME/.local/share/vsm/history.toml",
+        "Holds when, how often and with which exit status every session was opened.",
+    ),
+    (
+        "$HOME/.local/share/vsm/trash",
+        "Holds the sessions moved there by remove and prune, until they are restored or the \
+         trash is emptied.",
+    ),
+    (
+        "$HOME/.local/share/vsm/backups",
+        "Holds a copy of every session taken before relocate or portable rewrote it.",
+    ),
+];
+
+/// Exit codes vsm returns, and what they mean.
+const EXIT_STATUS: [(&str, &str); 3] = [
+    ("0", "Success."),
+    (
+        "1",
+        "A runtime failure, such as a session name that matched nothing, or problems found \
+         by check.",
+    ),
+    ("2", "The command line could not be parsed."),
+];
+
+/// Sections shared by the long help and every man page.
+const REFERENCE: [(&str, &[(&str, &str)]); 3] = [
+    ("ENVIRONMENT", &ENVIRONMENT),
+    ("FILES", &FILES),
+    ("EXIT STATUS", &EXIT_STATUS),
+];
+
+/// Builds the plain text reference shown at the bottom of `vsm --help`.
+pub fn long_help() -> String {
+    let mut help = String::new();
+    for (heading, entries) in REFERENCE {
+        help.push_str(&format!("{}:\n", heading));
+        for &(name, description) in entries {
+            help.push_str(&format!("  {}\n          {}\n", name, description));
+        }
+        help.push('\n');
+    }
+    help.trim_end().to_owned()
+}
+
+/// Writes `vsm.1`, and a `vsm-<sub-command>.1` page for every sub-command,
+/// into a directory. The directory is created if it does not exist.
+///
+/// # Arguments
+///     * dir Directory to write the man pages into.
+///
+/// # Returns
+///     * The paths of the written man pages.
+///
+/// # Errors
+///     * VsmRuntimeFault::ManPage captures all io errors.
+pub fn write_man_pages(dir: &Path) -> Result<Vec<PathBuf>, VsmRuntimeFault> {
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+
+    let source = format!(
+        "{} {}",
+        command.get_name(),
+        command.get_version().unwrap_or_default()
+    );
+    let mut pages = vec![command.clone()];
+    pages.extend(
+        command
+            .get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+            .cloned(),
+    );
+
+    let mut written: Vec<PathBuf> = vec![];
+    for page in pages {
+        match write_man_page(page, &source, dir) {
+            Ok(path) => written.push(path),
+            Err(e) => {
+                return Err(VsmRuntimeFault::ManPage {
+                    msg: format!("{} => {}", dir.display(), e),
+                })
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Renders a single man page, with the reference sections appended, and
+/// writes it to disk.
+fn write_man_page(command: Command, source: &str, dir: &Path) -> Result<PathBuf, io::Error> {
+    let has_version = command.get_version().is_some();
+    let has_author = command.get_author().is_some();
+    let has_subcommands = command.has_subcommands();
+    let man = Man::new(command).source(source);
+    let mut page: Vec<u8> = vec![];
+    man.render_title(&mut page)?;
+    man.render_name_section(&mut page)?;
+    man.render_synopsis_section(&mut page)?;
+    man.render_description_section(&mut page)?;
+    man.render_options_section(&mut page)?;
+    if has_subcommands {
+        man.render_subcommands_section(&mut page)?;
+    }
+
+    let mut roff = Roff::default();
+    for (heading, entries) in REFERENCE {
+        roff.control("SH", [heading]);
+        for &(name, description) in entries {
+            roff.control("TP", []);
+            roff.text([bold(name)]);
+            roff.text([roman(description)]);
+        }
+    }
+    roff.to_writer(&mut page)?;
+
+    if has_version {
+        man.render_version_section(&mut page)?;
+    }
+    if has_author {
+        man.render_authors_section(&mut page)?;
+    }
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(man.get_filename());
+    std::fs::write(&path, page)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{long_help, write_man_pages};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_write_man_pages_covers_every_subcommand() {
+        let sandbox = test_dir("man");
+        let dir = sandbox.path();
+        let pages = write_man_pages(dir).expect("man pages are written");
+        let names: Vec<String> = pages
+            .iter()
+            .filter_map(|page| page.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.first().map(String::as_str), Some("vsm.1"));
+        assert!(names.contains(&"vsm-open.1".to_owned()));
+        assert!(!names.iter().any(|name| name.contains("__complete")));
+    }
+
+    #[test]
+    fn test_long_help_documents_environment() {
+        assert!(long_help().contains("VIM_SESSIONS"));
+    }
+}
@@ -1,6 +1,11 @@
 //!Expose public cli interfaces
 
 mod commands;
+mod completion;
+mod manual;
 mod wrapper;
 
+pub use commands::{ArgumentName, SubCommandName};
+pub use completion::completion_script;
+pub use manual::write_man_pages;
 pub use wrapper::Cli;
@@ -1,5 +1,6 @@
 //! A wrapper around the clap library
 
+use std::ffi::OsString;
 use std::fmt::{Display, Formatter, Result};
 
 use clap::{
@@ -8,7 +9,12 @@ use clap::{
 };
 use derive_getters::Getters;
 
-use super::commands::{ActiveCommand, Argument, OptionalCommandName, SubCommandName};
+use super::commands::{ActiveCommand, Argument, ArgumentName, OptionalCommandName, SubCommandName};
+use super::completion::COMPLETION_SHELLS;
+use super::manual::long_help;
+use crate::error::VsmRuntimeFault;
+use crate::session::{AgeBy, ClashPolicy, SessionSort};
+use crate::ui::ListFormat;
 
 /// Wrapper around the clap ArgMatches object
 #[derive(Debug, Getters)]
@@ -32,13 +38,61 @@ impl Default for Cli {
 }
 
 impl Cli {
-    /// Builds a new Cli object
+    /// Builds a new Cli object from the process arguments, exits the process
+    /// on invalid arguments, `--help` and `--version`.
     #[must_use]
     pub fn new() -> Self {
-        let arg_matches: ArgMatches = Command::new(crate_name!())
+        Self::build_active_command(&Self::internal_command().get_matches())
+    }
+
+    /// Builds a new Cli object from explicit arguments, the first of which is
+    /// the binary name.
+    ///
+    /// # Errors
+    ///     - VsmRuntimeFault::CommandLine if the arguments can not be parsed,
+    ///       this includes `--help` and `--version`.
+    pub fn try_from_args<I, T>(args: I) -> std::result::Result<Self, VsmRuntimeFault>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        match Self::internal_command().try_get_matches_from(args) {
+            Ok(arg_matches) => Ok(Self::build_active_command(&arg_matches)),
+            Err(e) => Err(VsmRuntimeFault::CommandLine { msg: e.to_string() }),
+        }
+    }
+
+    /// Builds the full clap command tree, including the hidden sub-commands
+    /// that only other programs are meant to call.
+    fn internal_command() -> Command {
+        Self::command()
+            .subcommand(
+                Command::new(SubCommandName::COMPLETE)
+                    .hide(true)
+                    .about("Print the session names, used by the completion scripts"),
+            )
+            .subcommand(
+                Command::new(SubCommandName::GENERATE_MAN)
+                    .hide(true)
+                    .about("Write the man pages into a directory")
+                    .arg(
+                        Arg::new(ArgumentName::DIRECTORY)
+                            .required(true)
+                            .help("Directory to write the man pages into"),
+                    ),
+            )
+    }
+
+    /// Builds the clap command tree of the user facing sub-commands. Everything
+    /// that describes the command line, such as shell completions, is
+    /// generated from it.
+    #[must_use]
+    pub fn command() -> Command {
+        Command::new(crate_name!())
             .author(crate_authors!())
             .version(crate_version!())
             .about(crate_description!())
+            .after_long_help(long_help())
             .subcommand_required(true)
             .arg(
                 Arg::new(OptionalCommandName::DEBUG)
@@ -51,26 +105,552 @@ impl Cli {
             .subcommand(
                 Command::new(SubCommandName::LIST)
                     .arg_required_else_help(false)
-                    .about("List all available vim session files"),
+                    .about("List all available vim session files")
+                    .arg(
+                        Arg::new(ArgumentName::FORMAT)
+                            .required(false)
+                            .help(
+                                "Print a machine readable listing to stdout. tsv columns are \
+                                 name, path, size, mtime, description, tags and source, \
+                                 with tabs and newlines escaped as \\t and \\n, plain and \
+                                 null print names only",
+                            )
+                            .short('f')
+                            .long(ArgumentName::FORMAT)
+                            .value_parser(ListFormat::NAMES),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::PINNED)
+                            .required(false)
+                            .help("Only list the pinned sessions")
+                            .short('p')
+                            .long(ArgumentName::PINNED)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Self::sort_arg()),
             )
             .subcommand(
                 Command::new(SubCommandName::OPEN)
                     .arg_required_else_help(false)
-                    .about("Load a session file"),
+                    .about("Load a session file")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session to open, matched exactly, then by unique prefix, \
+                         then fuzzily. '-' reopens the last session. Prompts if omitted or \
+                         ambiguous",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::LAST)
+                            .required(false)
+                            .help("Reopen the session that was opened last, without prompting")
+                            .short('l')
+                            .long(ArgumentName::LAST)
+                            .conflicts_with(ArgumentName::SESSION)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::HERE)
+                            .required(false)
+                            .help(
+                                "Open the session whose working directory matches the current \
+                                 one most closely, without prompting",
+                            )
+                            .long(ArgumentName::HERE)
+                            .conflicts_with_all([ArgumentName::SESSION, ArgumentName::LAST])
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Self::sort_arg()),
             )
             .subcommand(
                 Command::new(SubCommandName::REMOVE)
                     .arg_required_else_help(false)
-                    .about("Remove a session file"),
+                    .about("Move session files to the trash")
+                    .arg(
+                        Arg::new(ArgumentName::SESSION)
+                            .required(false)
+                            .num_args(0..)
+                            .help(
+                                "Names or shell-style globs (e.g. 'tmp-*') of the sessions to \
+                                 remove. Prompts if omitted",
+                            ),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::YES)
+                            .required(false)
+                            .help("Remove without asking for confirmation")
+                            .short('y')
+                            .long(ArgumentName::YES)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::DRY_RUN)
+                            .required(false)
+                            .help("Print the files that would be removed, without removing them")
+                            .short('n')
+                            .long(ArgumentName::DRY_RUN)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::FORCE)
+                            .required(false)
+                            .help("Remove pinned sessions as well, they are skipped otherwise")
+                            .short('f')
+                            .long(ArgumentName::FORCE)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Self::permanent_arg()),
+            )
+            .subcommand(
+                Command::new(SubCommandName::PRUNE)
+                    .arg_required_else_help(false)
+                    .about(
+                        "Remove stale sessions matching every given criterion, or the [prune] \
+                         policy of the config.toml. Pinned sessions are kept",
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::OLDER_THAN)
+                            .required(false)
+                            .help("Prune sessions older than this, e.g. 30d, 12h or 2w")
+                            .long(ArgumentName::OLDER_THAN)
+                            .value_name("AGE"),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::AGE_BY)
+                            .required(false)
+                            .help("Measure the age by the file's mtime, or the last open")
+                            .long(ArgumentName::AGE_BY)
+                            .requires(ArgumentName::OLDER_THAN)
+                            .value_parser(AgeBy::NAMES),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::BROKEN)
+                            .required(false)
+                            .help("Prune sessions whose directory or files are missing")
+                            .long(ArgumentName::BROKEN)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::NEVER_OPENED)
+                            .required(false)
+                            .help("Prune sessions that were never opened through vsm")
+                            .long(ArgumentName::NEVER_OPENED)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::KEEP)
+                            .required(false)
+                            .help("Never prune the N most recently used sessions, needs another criterion")
+                            .long(ArgumentName::KEEP)
+                            .value_name("N")
+                            .value_parser(clap::value_parser!(usize)),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::YES)
+                            .required(false)
+                            .help("Remove without asking for confirmation")
+                            .short('y')
+                            .long(ArgumentName::YES)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::DRY_RUN)
+                            .required(false)
+                            .help("Only preview the sessions that would be removed")
+                            .short('n')
+                            .long(ArgumentName::DRY_RUN)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Self::permanent_arg()),
+            )
+            .subcommand(
+                Command::new(SubCommandName::RESTORE)
+                    .arg_required_else_help(false)
+                    .about("Move a removed session back out of the trash")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the trashed session, the most recently trashed one wins. \
+                         Prompts if omitted",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::FORCE)
+                            .required(false)
+                            .help("Overwrite a session that took its place")
+                            .short('f')
+                            .long(ArgumentName::FORCE)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::TRASH)
+                    .arg_required_else_help(true)
+                    .subcommand_required(true)
+                    .about("List or empty the trash of removed sessions")
+                    .subcommand(
+                        Command::new(SubCommandName::TRASH_LIST).about("List the trashed sessions"),
+                    )
+                    .subcommand(
+                        Command::new(SubCommandName::TRASH_EMPTY)
+                            .about("Delete trashed sessions for good")
+                            .arg(
+                                Arg::new(ArgumentName::OLDER_THAN)
+                                    .required(false)
+                                    .help("Only delete sessions trashed longer ago, e.g. 30d")
+                                    .long(ArgumentName::OLDER_THAN)
+                                    .value_name("AGE"),
+                            )
+                            .arg(
+                                Arg::new(ArgumentName::YES)
+                                    .required(false)
+                                    .help("Delete without asking for confirmation")
+                                    .short('y')
+                                    .long(ArgumentName::YES)
+                                    .action(ArgAction::SetTrue),
+                            ),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::EXPORT)
+                    .arg_required_else_help(true)
+                    .about("Write sessions, their metadata and a manifest into a tar bundle")
+                    .arg(
+                        Arg::new(ArgumentName::ARCHIVE)
+                            .required(true)
+                            .help("Path of the bundle to write, e.g. sessions.tar")
+                            .value_hint(clap::ValueHint::FilePath),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::SESSION)
+                            .required(false)
+                            .num_args(0..)
+                            .help(
+                                "Names or shell-style globs of the sessions to export. Exports \
+                                 every session if omitted",
+                            ),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::IMPORT)
+                    .arg_required_else_help(true)
+                    .about("Read sessions from a bundle written by export")
+                    .arg(
+                        Arg::new(ArgumentName::ARCHIVE)
+                            .required(true)
+                            .help("Path of the bundle to read")
+                            .value_hint(clap::ValueHint::FilePath),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::ON_CLASH)
+                            .required(false)
+                            .help(
+                                "What to do with sessions whose name is taken. Prompts for each \
+                                 if omitted, and skips them without a terminal",
+                            )
+                            .long(ArgumentName::ON_CLASH)
+                            .value_parser(ClashPolicy::NAMES),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::REWRITE_HOME)
+                            .required(false)
+                            .help("Rewrite paths under the exporting $HOME to the local $HOME")
+                            .long(ArgumentName::REWRITE_HOME)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::DRY_RUN)
+                            .required(false)
+                            .help("Only show what would be imported")
+                            .short('n')
+                            .long(ArgumentName::DRY_RUN)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::RELOCATE)
+                    .arg_required_else_help(true)
+                    .about(
+                        "Rewrite the cd, badd, edit and argadd paths of sessions from one \
+                         directory to another, after a preview and keeping a backup",
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::FROM)
+                            .required(true)
+                            .help("Directory the project was moved from, e.g. ~/src/x")
+                            .value_hint(clap::ValueHint::DirPath),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::TO)
+                            .required(true)
+                            .help("Directory the project was moved to, e.g. ~/code/x")
+                            .value_hint(clap::ValueHint::DirPath),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::SESSION)
+                            .required_unless_present(ArgumentName::ALL)
+                            .num_args(1..)
+                            .help("Names or shell-style globs of the sessions to relocate"),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::ALL)
+                            .required(false)
+                            .help("Relocate every session")
+                            .short('a')
+                            .long(ArgumentName::ALL)
+                            .conflicts_with(ArgumentName::SESSION)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::YES)
+                            .required(false)
+                            .help("Rewrite without asking for confirmation")
+                            .short('y')
+                            .long(ArgumentName::YES)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::DRY_RUN)
+                            .required(false)
+                            .help("Only preview the changes")
+                            .short('n')
+                            .long(ArgumentName::DRY_RUN)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::PORTABLE)
+                    .arg_required_else_help(true)
+                    .about(
+                        "Spell the paths of sessions under $HOME with ~, so they work for \
+                         another user or machine, keeping a backup",
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::SESSION)
+                            .required_unless_present(ArgumentName::ALL)
+                            .num_args(1..)
+                            .help("Names or shell-style globs of the sessions to make portable"),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::ALL)
+                            .required(false)
+                            .help("Make every session portable")
+                            .short('a')
+                            .long(ArgumentName::ALL)
+                            .conflicts_with(ArgumentName::SESSION)
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::DRY_RUN)
+                            .required(false)
+                            .help("Only preview the changes")
+                            .short('n')
+                            .long(ArgumentName::DRY_RUN)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::NEW)
+                    .arg_required_else_help(true)
+                    .about(
+                        "Start the editor for a new session, which is saved when the editor exits",
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::NAME)
+                            .required(true)
+                            .help("Name of the new session"),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::FILES)
+                            .required(false)
+                            .num_args(0..)
+                            .help("Files to open, relative to --cwd if given"),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::CWD)
+                            .required(false)
+                            .help("Working directory to start the editor in")
+                            .long(ArgumentName::CWD)
+                            .value_hint(clap::ValueHint::DirPath),
+                    )
+                    .arg(
+                        Arg::new(ArgumentName::FORCE)
+                            .required(false)
+                            .help("Move a session that already has the name to the trash first")
+                            .short('f')
+                            .long(ArgumentName::FORCE)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::SHOW)
+                    .arg_required_else_help(false)
+                    .about("Show the working directory, layout and buffers of a session")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session to show, matched like open. Prompts if omitted \
+                         or ambiguous",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::JSON)
+                            .required(false)
+                            .help("Print the details as JSON")
+                            .long(ArgumentName::JSON)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::CHECK)
+                    .arg_required_else_help(false)
+                    .about("Check every session for missing directories and files")
+                    .arg(
+                        Arg::new(ArgumentName::ONLY_BROKEN)
+                            .required(false)
+                            .help(
+                                "Only print the names of broken sessions to stdout, one per \
+                                 line, e.g. to feed them into remove",
+                            )
+                            .long(ArgumentName::ONLY_BROKEN)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::RENAME)
+                    .arg_required_else_help(false)
+                    .about("Rename a session file, along with its x.vim companion")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session to rename, matched like open. Prompts if omitted \
+                         or ambiguous",
+                    ))
+                    .arg(Arg::new(ArgumentName::NEW_NAME).required(false).help(
+                        "New name of the session, group/name moves it to another group. \
+                         Prompts if omitted",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::FORCE)
+                            .required(false)
+                            .help("Move a session that already has the new name to the trash")
+                            .short('f')
+                            .long(ArgumentName::FORCE)
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::DESCRIBE)
+                    .arg_required_else_help(false)
+                    .about("Set the one line description of a session, or print it")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session, matched like open. Prompts if omitted or \
+                         ambiguous",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::TEXT)
+                            .required(false)
+                            .num_args(0..)
+                            .help("The description, an empty one clears it. Prompts if omitted"),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::NOTE)
+                    .arg_required_else_help(false)
+                    .about("Set the free-form note of a session, or print it")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session, matched like open. Prompts if omitted or \
+                         ambiguous",
+                    ))
+                    .arg(
+                        Arg::new(ArgumentName::TEXT)
+                            .required(false)
+                            .num_args(0..)
+                            .help("The note, an empty one clears it. Prompts if omitted"),
+                    ),
+            )
+            .subcommand(
+                Command::new(SubCommandName::TAG)
+                    .arg_required_else_help(true)
+                    .subcommand_required(true)
+                    .about("Add or remove tags of a session")
+                    .subcommand(Self::tag_command(
+                        SubCommandName::TAG_ADD,
+                        "Add tags to a session",
+                    ))
+                    .subcommand(Self::tag_command(
+                        SubCommandName::TAG_REMOVE,
+                        "Remove tags from a session",
+                    )),
+            )
+            .subcommand(
+                Command::new(SubCommandName::PIN)
+                    .arg_required_else_help(false)
+                    .about("Pin a session, so it is offered first and protected from removal")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session, matched like open. Prompts if omitted or \
+                         ambiguous",
+                    )),
+            )
+            .subcommand(
+                Command::new(SubCommandName::UNPIN)
+                    .arg_required_else_help(false)
+                    .about("Unpin a session")
+                    .arg(Arg::new(ArgumentName::SESSION).required(false).help(
+                        "Name of the session, matched like open. Prompts if omitted or \
+                         ambiguous",
+                    )),
             )
             .subcommand(
                 Command::new(SubCommandName::VARIANT)
                     .arg_required_else_help(false)
                     .about("Change the variation of vim you want to open sessions with"),
             )
-            .get_matches();
+            .subcommand(
+                Command::new(SubCommandName::COMPLETIONS)
+                    .arg_required_else_help(true)
+                    .about("Print a shell completion script to stdout")
+                    .arg(
+                        Arg::new(ArgumentName::SHELL)
+                            .required(true)
+                            .help("Shell to generate the completion script for")
+                            .value_parser(COMPLETION_SHELLS),
+                    ),
+            )
+    }
+
+    /// Private helper function to build the --permanent flag shared by remove
+    /// and prune.
+    fn permanent_arg() -> Arg {
+        Arg::new(ArgumentName::PERMANENT)
+            .required(false)
+            .help("Delete the sessions for good, instead of moving them to the trash")
+            .long(ArgumentName::PERMANENT)
+            .action(ArgAction::SetTrue)
+    }
 
-        Self::build_active_command(&arg_matches)
+    /// Private helper function to build the --sort option shared by list and
+    /// the pickers.
+    fn sort_arg() -> Arg {
+        Arg::new(ArgumentName::SORT)
+            .required(false)
+            .help("Order to list and offer the sessions in")
+            .short('s')
+            .long(ArgumentName::SORT)
+            .value_parser(SessionSort::NAMES)
+    }
+
+    /// Private helper function to build the nested sub-commands of tag, which
+    /// only differ in what they do.
+    ///
+    /// # Arguments
+    ///     - name Name of the nested sub-command
+    ///     - about Help text of the nested sub-command
+    fn tag_command(name: &'static str, about: &'static str) -> Command {
+        Command::new(name)
+            .arg_required_else_help(true)
+            .about(about)
+            .arg(
+                Arg::new(ArgumentName::SESSION)
+                    .required(true)
+                    .help("Name of the session, matched like open"),
+            )
+            .arg(
+                Arg::new(ArgumentName::TAGS)
+                    .required(true)
+                    .num_args(1..)
+                    .help("Tags, a leading '#' is ignored"),
+            )
     }
 
     /// Private helper function to build the proper active command.
@@ -80,18 +660,303 @@ impl Cli {
     #[allow(clippy::unreachable)]
     fn build_active_command(matches: &ArgMatches) -> Self {
         let active_command: ActiveCommand = match matches.subcommand() {
-            Some((SubCommandName::LIST, _)) => {
-                ActiveCommand::new(SubCommandName::LIST, Argument::default())
+            Some((SubCommandName::LIST, sub_matches)) => ActiveCommand::new(
+                SubCommandName::LIST,
+                Argument::default()
+                    .with_option(
+                        ArgumentName::FORMAT,
+                        sub_matches.get_one::<String>(ArgumentName::FORMAT).cloned(),
+                    )
+                    .with_option(
+                        ArgumentName::SORT,
+                        sub_matches.get_one::<String>(ArgumentName::SORT).cloned(),
+                    )
+                    .with_flag(
+                        ArgumentName::PINNED,
+                        sub_matches.get_flag(ArgumentName::PINNED),
+                    ),
+            ),
+            Some((SubCommandName::OPEN, sub_matches)) => ActiveCommand::new(
+                SubCommandName::OPEN,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_flag(ArgumentName::LAST, sub_matches.get_flag(ArgumentName::LAST))
+                .with_flag(ArgumentName::HERE, sub_matches.get_flag(ArgumentName::HERE))
+                .with_option(
+                    ArgumentName::SORT,
+                    sub_matches.get_one::<String>(ArgumentName::SORT).cloned(),
+                ),
+            ),
+            Some((SubCommandName::REMOVE, sub_matches)) => ActiveCommand::new(
+                SubCommandName::REMOVE,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_flag(ArgumentName::YES, sub_matches.get_flag(ArgumentName::YES))
+                .with_flag(
+                    ArgumentName::DRY_RUN,
+                    sub_matches.get_flag(ArgumentName::DRY_RUN),
+                )
+                .with_flag(
+                    ArgumentName::FORCE,
+                    sub_matches.get_flag(ArgumentName::FORCE),
+                )
+                .with_flag(
+                    ArgumentName::PERMANENT,
+                    sub_matches.get_flag(ArgumentName::PERMANENT),
+                ),
+            ),
+            Some((SubCommandName::PRUNE, sub_matches)) => ActiveCommand::new(
+                SubCommandName::PRUNE,
+                Argument::default()
+                    .with_option(
+                        ArgumentName::OLDER_THAN,
+                        sub_matches
+                            .get_one::<String>(ArgumentName::OLDER_THAN)
+                            .cloned(),
+                    )
+                    .with_option(
+                        ArgumentName::AGE_BY,
+                        sub_matches.get_one::<String>(ArgumentName::AGE_BY).cloned(),
+                    )
+                    .with_option(
+                        ArgumentName::KEEP,
+                        sub_matches
+                            .get_one::<usize>(ArgumentName::KEEP)
+                            .map(ToString::to_string),
+                    )
+                    .with_flag(
+                        ArgumentName::BROKEN,
+                        sub_matches.get_flag(ArgumentName::BROKEN),
+                    )
+                    .with_flag(
+                        ArgumentName::NEVER_OPENED,
+                        sub_matches.get_flag(ArgumentName::NEVER_OPENED),
+                    )
+                    .with_flag(ArgumentName::YES, sub_matches.get_flag(ArgumentName::YES))
+                    .with_flag(
+                        ArgumentName::DRY_RUN,
+                        sub_matches.get_flag(ArgumentName::DRY_RUN),
+                    )
+                    .with_flag(
+                        ArgumentName::PERMANENT,
+                        sub_matches.get_flag(ArgumentName::PERMANENT),
+                    ),
+            ),
+            Some((SubCommandName::RESTORE, sub_matches)) => ActiveCommand::new(
+                SubCommandName::RESTORE,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_flag(
+                    ArgumentName::FORCE,
+                    sub_matches.get_flag(ArgumentName::FORCE),
+                ),
+            ),
+            Some((SubCommandName::TRASH, sub_matches)) => match sub_matches.subcommand() {
+                Some((action, trash_matches)) => ActiveCommand::new(
+                    SubCommandName::TRASH,
+                    Argument::default()
+                        .with_option(ArgumentName::ACTION, Some(action.to_owned()))
+                        .with_option(
+                            ArgumentName::OLDER_THAN,
+                            trash_matches
+                                .try_get_one::<String>(ArgumentName::OLDER_THAN)
+                                .ok()
+                                .flatten()
+                                .cloned(),
+                        )
+                        .with_flag(
+                            ArgumentName::YES,
+                            trash_matches
+                                .try_get_one::<bool>(ArgumentName::YES)
+                                .ok()
+                                .flatten()
+                                .is_some_and(|yes| *yes),
+                        ),
+                ),
+                None => unreachable!(),
+            },
+            Some((SubCommandName::NEW, sub_matches)) => ActiveCommand::new(
+                SubCommandName::NEW,
+                Argument::new(
+                    ArgumentName::FILES,
+                    Self::values_of(sub_matches, ArgumentName::FILES),
+                )
+                .with_option(
+                    ArgumentName::NAME,
+                    sub_matches.get_one::<String>(ArgumentName::NAME).cloned(),
+                )
+                .with_option(
+                    ArgumentName::CWD,
+                    sub_matches.get_one::<String>(ArgumentName::CWD).cloned(),
+                )
+                .with_flag(
+                    ArgumentName::FORCE,
+                    sub_matches.get_flag(ArgumentName::FORCE),
+                ),
+            ),
+            Some((SubCommandName::EXPORT, sub_matches)) => ActiveCommand::new(
+                SubCommandName::EXPORT,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_option(
+                    ArgumentName::ARCHIVE,
+                    sub_matches
+                        .get_one::<String>(ArgumentName::ARCHIVE)
+                        .cloned(),
+                ),
+            ),
+            Some((SubCommandName::IMPORT, sub_matches)) => ActiveCommand::new(
+                SubCommandName::IMPORT,
+                Argument::default()
+                    .with_option(
+                        ArgumentName::ARCHIVE,
+                        sub_matches
+                            .get_one::<String>(ArgumentName::ARCHIVE)
+                            .cloned(),
+                    )
+                    .with_option(
+                        ArgumentName::ON_CLASH,
+                        sub_matches
+                            .get_one::<String>(ArgumentName::ON_CLASH)
+                            .cloned(),
+                    )
+                    .with_flag(
+                        ArgumentName::REWRITE_HOME,
+                        sub_matches.get_flag(ArgumentName::REWRITE_HOME),
+                    )
+                    .with_flag(
+                        ArgumentName::DRY_RUN,
+                        sub_matches.get_flag(ArgumentName::DRY_RUN),
+                    ),
+            ),
+            Some((SubCommandName::RELOCATE, sub_matches)) => ActiveCommand::new(
+                SubCommandName::RELOCATE,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_option(
+                    ArgumentName::FROM,
+                    sub_matches.get_one::<String>(ArgumentName::FROM).cloned(),
+                )
+                .with_option(
+                    ArgumentName::TO,
+                    sub_matches.get_one::<String>(ArgumentName::TO).cloned(),
+                )
+                .with_flag(ArgumentName::ALL, sub_matches.get_flag(ArgumentName::ALL))
+                .with_flag(ArgumentName::YES, sub_matches.get_flag(ArgumentName::YES))
+                .with_flag(
+                    ArgumentName::DRY_RUN,
+                    sub_matches.get_flag(ArgumentName::DRY_RUN),
+                ),
+            ),
+            Some((SubCommandName::PORTABLE, sub_matches)) => ActiveCommand::new(
+                SubCommandName::PORTABLE,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_flag(ArgumentName::ALL, sub_matches.get_flag(ArgumentName::ALL))
+                .with_flag(
+                    ArgumentName::DRY_RUN,
+                    sub_matches.get_flag(ArgumentName::DRY_RUN),
+                ),
+            ),
+            Some((SubCommandName::SHOW, sub_matches)) => ActiveCommand::new(
+                SubCommandName::SHOW,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_flag(ArgumentName::JSON, sub_matches.get_flag(ArgumentName::JSON)),
+            ),
+            Some((SubCommandName::CHECK, sub_matches)) => ActiveCommand::new(
+                SubCommandName::CHECK,
+                Argument::default().with_flag(
+                    ArgumentName::ONLY_BROKEN,
+                    sub_matches.get_flag(ArgumentName::ONLY_BROKEN),
+                ),
+            ),
+            Some((SubCommandName::RENAME, sub_matches)) => ActiveCommand::new(
+                SubCommandName::RENAME,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                )
+                .with_option(
+                    ArgumentName::NEW_NAME,
+                    sub_matches
+                        .get_one::<String>(ArgumentName::NEW_NAME)
+                        .cloned(),
+                )
+                .with_flag(
+                    ArgumentName::FORCE,
+                    sub_matches.get_flag(ArgumentName::FORCE),
+                ),
+            ),
+            Some((SubCommandName::DESCRIBE, sub_matches)) => {
+                ActiveCommand::new(SubCommandName::DESCRIBE, Self::text_argument(sub_matches))
             }
-            Some((SubCommandName::OPEN, _)) => {
-                ActiveCommand::new(SubCommandName::OPEN, Argument::default())
-            }
-            Some((SubCommandName::REMOVE, _)) => {
-                ActiveCommand::new(SubCommandName::REMOVE, Argument::default())
+            Some((SubCommandName::NOTE, sub_matches)) => {
+                ActiveCommand::new(SubCommandName::NOTE, Self::text_argument(sub_matches))
             }
+            Some((SubCommandName::TAG, sub_matches)) => match sub_matches.subcommand() {
+                Some((action, tag_matches)) => ActiveCommand::new(
+                    SubCommandName::TAG,
+                    Argument::new(
+                        ArgumentName::SESSION,
+                        Self::values_of(tag_matches, ArgumentName::SESSION),
+                    )
+                    .with_option(
+                        ArgumentName::TAGS,
+                        Some(Self::values_of(tag_matches, ArgumentName::TAGS).join(" ")),
+                    )
+                    .with_option(ArgumentName::ACTION, Some(action.to_owned())),
+                ),
+                None => unreachable!(),
+            },
+            Some((SubCommandName::PIN, sub_matches)) => ActiveCommand::new(
+                SubCommandName::PIN,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                ),
+            ),
+            Some((SubCommandName::UNPIN, sub_matches)) => ActiveCommand::new(
+                SubCommandName::UNPIN,
+                Argument::new(
+                    ArgumentName::SESSION,
+                    Self::values_of(sub_matches, ArgumentName::SESSION),
+                ),
+            ),
             Some((SubCommandName::VARIANT, _)) => {
                 ActiveCommand::new(SubCommandName::VARIANT, Argument::default())
             }
+            Some((SubCommandName::COMPLETIONS, sub_matches)) => ActiveCommand::new(
+                SubCommandName::COMPLETIONS,
+                Argument::new(
+                    ArgumentName::SHELL,
+                    Self::values_of(sub_matches, ArgumentName::SHELL),
+                ),
+            ),
+            Some((SubCommandName::COMPLETE, _)) => {
+                ActiveCommand::new(SubCommandName::COMPLETE, Argument::default())
+            }
+            Some((SubCommandName::GENERATE_MAN, sub_matches)) => ActiveCommand::new(
+                SubCommandName::GENERATE_MAN,
+                Argument::new(
+                    ArgumentName::DIRECTORY,
+                    Self::values_of(sub_matches, ArgumentName::DIRECTORY),
+                ),
+            ),
             _ => unreachable!(),
         };
 
@@ -101,6 +966,34 @@ impl Cli {
         }
     }
 
+    /// Private helper function to build the argument of describe and note, the
+    /// words of the text are joined with single spaces.
+    ///
+    /// # Arguments
+    ///     - matches clap::ArgMatches object of the sub-command
+    fn text_argument(matches: &ArgMatches) -> Argument {
+        let text = matches
+            .get_many::<String>(ArgumentName::TEXT)
+            .map(|words| words.cloned().collect::<Vec<String>>().join(" "));
+        Argument::new(
+            ArgumentName::SESSION,
+            Self::values_of(matches, ArgumentName::SESSION),
+        )
+        .with_option(ArgumentName::TEXT, text)
+    }
+
+    /// Private helper function to collect every value given to an argument.
+    ///
+    /// # Arguments
+    ///     - matches clap::ArgMatches object of the sub-command
+    ///     - name Name of the argument
+    fn values_of(matches: &ArgMatches, name: &str) -> Vec<String> {
+        matches
+            .get_many::<String>(name)
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Returns true if the active sub-command is list
     #[must_use]
     pub fn list(&self) -> bool {
@@ -119,9 +1012,123 @@ impl Cli {
         self.active_command.command() == SubCommandName::REMOVE
     }
 
+    /// Returns true if the active sub-command is prune
+    #[must_use]
+    pub fn prune(&self) -> bool {
+        self.active_command.command() == SubCommandName::PRUNE
+    }
+
+    /// Returns true if the active sub-command is restore
+    #[must_use]
+    pub fn restore(&self) -> bool {
+        self.active_command.command() == SubCommandName::RESTORE
+    }
+
+    /// Returns true if the active sub-command is trash
+    #[must_use]
+    pub fn trash(&self) -> bool {
+        self.active_command.command() == SubCommandName::TRASH
+    }
+
+    /// Returns true if the active sub-command is export
+    #[must_use]
+    pub fn export(&self) -> bool {
+        self.active_command.command() == SubCommandName::EXPORT
+    }
+
+    /// Returns true if the active sub-command is import
+    #[must_use]
+    pub fn import(&self) -> bool {
+        self.active_command.command() == SubCommandName::IMPORT
+    }
+
+    /// Returns true if the active sub-command is relocate
+    #[must_use]
+    pub fn relocate(&self) -> bool {
+        self.active_command.command() == SubCommandName::RELOCATE
+    }
+
+    /// Returns true if the active sub-command is portable
+    #[must_use]
+    pub fn portable(&self) -> bool {
+        self.active_command.command() == SubCommandName::PORTABLE
+    }
+
+    /// Returns true if the active sub-command is new
+    #[must_use]
+    pub fn create(&self) -> bool {
+        self.active_command.command() == SubCommandName::NEW
+    }
+
+    /// Returns true if the active sub-command is show
+    #[must_use]
+    pub fn show(&self) -> bool {
+        self.active_command.command() == SubCommandName::SHOW
+    }
+
+    /// Returns true if the active sub-command is check
+    #[must_use]
+    pub fn check(&self) -> bool {
+        self.active_command.command() == SubCommandName::CHECK
+    }
+
+    /// Returns true if the active sub-command is rename
+    #[must_use]
+    pub fn rename(&self) -> bool {
+        self.active_command.command() == SubCommandName::RENAME
+    }
+
+    /// Returns true if the active sub-command is describe
+    #[must_use]
+    pub fn describe(&self) -> bool {
+        self.active_command.command() == SubCommandName::DESCRIBE
+    }
+
+    /// Returns true if the active sub-command is note
+    #[must_use]
+    pub fn note(&self) -> bool {
+        self.active_command.command() == SubCommandName::NOTE
+    }
+
+    /// Returns true if the active sub-command is tag
+    #[must_use]
+    pub fn tag(&self) -> bool {
+        self.active_command.command() == SubCommandName::TAG
+    }
+
+    /// Returns true if the active sub-command is pin
+    #[must_use]
+    pub fn pin(&self) -> bool {
+        self.active_command.command() == SubCommandName::PIN
+    }
+
+    /// Returns true if the active sub-command is unpin
+    #[must_use]
+    pub fn unpin(&self) -> bool {
+        self.active_command.command() == SubCommandName::UNPIN
+    }
+
     /// Returns true if the active sub-command is update
     #[must_use]
     pub fn variant(&self) -> bool {
         self.active_command.command() == SubCommandName::VARIANT
     }
+
+    /// Returns true if the active sub-command is completions
+    #[must_use]
+    pub fn completions(&self) -> bool {
+        self.active_command.command() == SubCommandName::COMPLETIONS
+    }
+
+    /// Returns true if the active sub-command is the hidden __complete
+    #[must_use]
+    pub fn complete(&self) -> bool {
+        self.active_command.command() == SubCommandName::COMPLETE
+    }
+
+    /// Returns true if the active sub-command is the hidden generate-man
+    #[must_use]
+    pub fn generate_man(&self) -> bool {
+        self.active_command.command() == SubCommandName::GENERATE_MAN
+    }
 }
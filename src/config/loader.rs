@@ -50,25 +50,30 @@ pub struct Paths {
     vsm_config_dir: String,
     /// Path including file name
     vsm_config_file: String,
+    /// Directory holding the data vsm records about sessions
+    vsm_data_dir: String,
 }
 
 impl fmt::Display for Paths {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Vsm config dir: {}\nVsm config file location: {}",
+            "Vsm config dir: {}\nVsm config file location: {}\nVsm data dir: {}",
             self.vsm_config_dir(),
             self.vsm_config_file(),
+            self.vsm_data_dir(),
         )
     }
 }
 
 impl Default for Paths {
     fn default() -> Self {
-        let config_dir = format!("{}/.config/vsm", Variables::default().home());
+        let home = Variables::default().home().clone();
+        let config_dir = format!("{}/.config/vsm", home);
         Self {
             vsm_config_dir: config_dir.clone(),
             vsm_config_file: format!("{}/config.toml", config_dir),
+            vsm_data_dir: format!("{}/.local/share/vsm", home),
         }
     }
 }
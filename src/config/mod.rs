@@ -4,4 +4,9 @@ mod loader;
 mod vsm_cf;
 
 pub use loader::ENVIRONMENT;
-pub use vsm_cf::{TomlConfigFile, VimVariant, SUPPORTED_VIM_VARIATIONS};
+#[cfg(feature = "cli")]
+pub use vsm_cf::SUPPORTED_VIM_VARIATIONS;
+pub use vsm_cf::{
+    AutosaveConfig, PortableConfig, ProjectsConfig, PruneConfig, SessionDirConfig, TomlConfigFile,
+    VimVariant,
+};
@@ -18,7 +18,7 @@ pub static SUPPORTED_VIM_VARIATIONS: Lazy<HashMap<&'static str, &'static str>> =
 });
 
 /// Holds the users preferred variation of vim, currently defaults to `neovim`.
-#[derive(Debug, Deserialize, Serialize, Getters)]
+#[derive(Debug, Clone, Deserialize, Serialize, Getters)]
 pub struct VimVariant {
     /// Active vim variant
     active_variant: String,
@@ -49,19 +49,161 @@ impl VimVariant {
     }
 }
 
+/// Makes the editor rewrite sessions opened through vsm, off unless the user
+/// opts in globally or for single sessions.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Getters)]
+#[serde(default)]
+pub struct AutosaveConfig {
+    /// Autosave every session
+    enabled: bool,
+    /// Names of the sessions to autosave when it is not enabled globally
+    sessions: Vec<String>,
+    /// Also save every this many seconds, 0 saves on exit only
+    interval: u64,
+    /// Also save whenever a buffer is entered
+    buf_enter: bool,
+}
+
+impl AutosaveConfig {
+    /// Builds an autosave configuration that applies to every session.
+    ///
+    /// # Arguments
+    ///     * interval Also save every this many seconds, 0 saves on exit only.
+    ///     * buf_enter Also save whenever a buffer is entered.
+    #[must_use]
+    pub const fn everywhere(interval: u64, buf_enter: bool) -> Self {
+        Self {
+            enabled: true,
+            sessions: vec![],
+            interval,
+            buf_enter,
+        }
+    }
+
+    /// Returns true if the session with the given name is autosaved.
+    #[must_use]
+    pub fn applies_to(&self, session: &str) -> bool {
+        self.enabled || self.sessions.iter().any(|name| name == session)
+    }
+}
+
+/// Keeps sessions free of the local home directory, so they can be synced
+/// between machines. Off unless the user opts in globally or for single
+/// sessions.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Getters)]
+#[serde(default)]
+pub struct PortableConfig {
+    /// Keep every session portable
+    enabled: bool,
+    /// Names of the sessions to keep portable when it is not enabled globally
+    sessions: Vec<String>,
+}
+
+impl PortableConfig {
+    /// Returns true if the session with the given name is kept portable.
+    #[must_use]
+    pub fn applies_to(&self, session: &str) -> bool {
+        self.enabled || self.sessions.iter().any(|name| name == session)
+    }
+}
+
+/// The policy `vsm prune` applies when it is given no criteria, see
+/// `PrunePolicy`. Empty unless the user configures it.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Getters)]
+#[serde(default)]
+pub struct PruneConfig {
+    /// Prune sessions older than this, e.g. `30d`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    older_than: Option<String>,
+    /// What the age is measured by, `mtime` or `last-open`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    age_by: Option<String>,
+    /// Prune sessions with a missing directory or files
+    broken: bool,
+    /// Prune sessions that were never opened through vsm
+    never_opened: bool,
+    /// Never prune the this many most recently used sessions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep: Option<usize>,
+}
+
+/// Where project-local `Session.vim` files are looked for. The working
+/// directory is searched up to its repository root unless turned off.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Getters)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    /// Walk up from the working directory to the repository root
+    discover: bool,
+    /// Roots whose projects are searched as well, a leading `~/` is the home
+    /// directory
+    workspaces: Vec<String>,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            discover: true,
+            workspaces: vec![],
+        }
+    }
+}
+
+/// An extra directory of session files, listed under `[[session_dirs]]` and
+/// read after the ones in `$VIM_SESSIONS`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct SessionDirConfig {
+    /// Namespace label, sessions are named `label:name`
+    #[serde(default)]
+    label: String,
+    /// Path to the directory, a leading `~/` is the home directory
+    path: String,
+}
+
 /// The `struct` is a composition of all above `structs`, this will be populated
 /// by the `config.toml`, or written to disk to create the `config.toml`
-#[derive(Debug, Default, Deserialize, Serialize, Getters)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Getters)]
 pub struct TomlConfigFile {
+    /// Levels of sub-directories scanned for sessions, absent from older
+    /// config files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scan_depth: Option<usize>,
     /// Holds above vim variant structure
     vim_variant: VimVariant,
+    /// Holds the autosave settings, absent from older config files
+    #[serde(default)]
+    autosave: AutosaveConfig,
+    /// Holds the default prune policy, absent from older config files
+    #[serde(default)]
+    prune: PruneConfig,
+    /// Holds the portability settings, absent from older config files
+    #[serde(default)]
+    portable: PortableConfig,
+    /// Holds the project discovery settings, absent from older config files
+    #[serde(default)]
+    projects: ProjectsConfig,
+    /// Extra session directories, absent from older config files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    session_dirs: Vec<SessionDirConfig>,
 }
 
 impl TomlConfigFile {
     /// Used when no configuration file is found on disk, denoting the first run
     /// of the program, the user is prompted to select their desired vim
     /// variation from a supported versions found installed on the system.
-    pub const fn new(vim_variant: VimVariant) -> Self {
-        Self { vim_variant }
+    pub fn new(vim_variant: VimVariant) -> Self {
+        Self {
+            scan_depth: None,
+            vim_variant,
+            autosave: AutosaveConfig::default(),
+            prune: PruneConfig::default(),
+            portable: PortableConfig::default(),
+            projects: ProjectsConfig::default(),
+            session_dirs: vec![],
+        }
+    }
+
+    /// Replaces the vim variant, keeping every other setting.
+    pub fn set_vim_variant(&mut self, vim_variant: VimVariant) {
+        self.vim_variant = vim_variant;
     }
 }
@@ -54,4 +54,116 @@ pub enum VsmRuntimeFault {
         /// custom message
         msg: String,
     },
+    /// used in app.rs. Error is returned when a session name given on the
+    /// command line does not resolve to exactly one session file.
+    #[error("Session lookup failure => {msg}")]
+    SessionResolution {
+        /// custom message
+        msg: String,
+    },
+    /// Used in ui/listing.rs. Error is returned when session data can not be
+    /// rendered in the requested output format.
+    #[error("Serialization failure => {msg}")]
+    Serialization {
+        /// custom message
+        msg: String,
+    },
+    /// used in app.rs. Consumes the input output errors raised while reading
+    /// a session file.
+    #[error("Failure to read session => {msg}")]
+    SessionFileRead {
+        /// custom message
+        msg: String,
+    },
+    /// used in app.rs. Error is returned when `vsm check` found problems, so
+    /// the process exits with a failure.
+    #[error("Session check failure => {msg}")]
+    SessionCheck {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/rename.rs. Error is returned when a session name can
+    /// not be used as a file name.
+    #[error("Invalid session name => {msg}")]
+    SessionName {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/rename.rs. Error is returned when a session can not be
+    /// renamed.
+    #[error("Failure to rename session => {msg}")]
+    SessionRename {
+        /// custom message
+        msg: String,
+    },
+    /// used in app.rs. Error is returned when a new session can not be
+    /// started.
+    #[error("Failure to create session => {msg}")]
+    SessionCreation {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/metadata.rs. Error is returned when the session
+    /// metadata store can not be read or written.
+    #[error("Session metadata failure => {msg}")]
+    Metadata {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/history.rs. Error is returned when the open history
+    /// can not be read or written, or a sort order is unknown.
+    #[error("Session history failure => {msg}")]
+    History {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/prune.rs. Error is returned when a prune policy is
+    /// invalid or missing.
+    #[error("Prune failure => {msg}")]
+    Prune {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/trash.rs. Error is returned when a session can not be
+    /// moved into or out of the trash.
+    #[error("Trash failure => {msg}")]
+    Trash {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/relocate.rs. Error is returned when the directories of
+    /// a relocation are invalid, or a session can not be backed up.
+    #[error("Relocation failure => {msg}")]
+    Relocate {
+        /// custom message
+        msg: String,
+    },
+    /// used in session/bundle.rs. Error is returned when a session bundle
+    /// can not be written or read, or does not match its manifest.
+    #[error("Session bundle failure => {msg}")]
+    Bundle {
+        /// custom message
+        msg: String,
+    },
+    /// used in cli/completion.rs. Error is returned when a completion script
+    /// can not be generated.
+    #[error("Completion failure => {msg}")]
+    Completion {
+        /// custom message
+        msg: String,
+    },
+    /// used in cli/manual.rs. Error is returned when the man pages can not be
+    /// written.
+    #[error("Man page failure => {msg}")]
+    ManPage {
+        /// custom message
+        msg: String,
+    },
+    /// used in cli/wrapper.rs. Error is returned when explicitly given
+    /// arguments can not be parsed.
+    #[error("{msg}")]
+    CommandLine {
+        /// custom message, as rendered by clap
+        msg: String,
+    },
 }
@@ -1,4 +1,29 @@
-//! Project-level documentation.
+//! `vsm` is an interactive command line vim session file manager. Besides the
+//! binary, the crate exposes the pieces it is built from so other tools can
+//! discover and open the same sessions.
+//!
+//! - [`SessionCatalog`] lists, resolves and removes the session files in
+//!   `$VIM_SESSIONS`, or any other directory.
+//! - [`SessionLauncher`] opens a session in the users vim variant.
+//! - [`TomlConfigFile`] and [`VimVariant`] model vsm's `config.toml`.
+//!
+//! All fallible operations return a [`VsmRuntimeFault`].
+//!
+//! ```no_run
+//! use vsm::{SessionCatalog, SessionLauncher, VsmRuntimeFault};
+//!
+//! fn open_first() -> Result<(), VsmRuntimeFault> {
+//!     let catalog = SessionCatalog::from_env()?;
+//!     if let Some(session) = catalog.sessions()?.first() {
+//!         SessionLauncher::from_env()?.launch(session.path())?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The command line interface, its prompts and its logger live behind the
+//! default `cli` feature. Embedders that only need the library can depend on
+//! vsm with `default-features = false`.
 
 #![allow(clippy::module_name_repetitions)]
 // clippy WARN level lints
@@ -21,7 +46,7 @@
 // clippy WARN level lints, that can be upgraded to DENY if preferred
 #![warn(
     clippy::float_arithmetic,
-    clippy::integer_arithmetic,
+    clippy::arithmetic_side_effects,
     clippy::modulo_arithmetic,
     clippy::as_conversions,
     clippy::assertions_on_result_states,
@@ -55,19 +80,35 @@
     clippy::shadow_unrelated,
     clippy::str_to_string,
     clippy::string_add,
-    clippy::string_to_string,
     clippy::unnecessary_self_imports,
     clippy::unneeded_field_pattern,
     clippy::unseparated_literal_suffix,
     clippy::verbose_file_reads
 )]
 
+#[cfg(feature = "cli")]
 mod app;
+#[cfg(feature = "cli")]
 mod cli;
 mod config;
 mod error;
+#[cfg(feature = "cli")]
 mod logger;
+mod session;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "cli")]
 mod ui;
 mod utils;
 
-pub use app::VimSessionManager;
+#[cfg(feature = "cli")]
+pub use app::{VimSessionManager, VimSessionManagerBuilder};
+pub use config::{
+    AutosaveConfig, PortableConfig, ProjectsConfig, PruneConfig, SessionDirConfig, TomlConfigFile,
+    VimVariant,
+};
+pub use error::VsmRuntimeFault;
+pub use session::{SessionCatalog, SessionHealth, SessionLauncher, SessionProblem, SessionRecord};
+#[cfg(feature = "cli")]
+pub use ui::{PickerEntry, Prompt, UserPromptRenderer};
+pub use utils::{SessionMatch, Shell};
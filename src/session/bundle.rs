@@ -0,0 +1,437 @@
+//! Carries sessions between machines. A bundle is a tar archive holding the
+//! chosen session files, their companion files and a manifest with their
+//! metadata, a sha256 of every file and the `$HOME` they were exported from.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tar::{Archive, Builder, Header};
+
+use super::companion::{companion_path, is_companion};
+use super::dirs::{SessionDirs, GROUP_SEPARATOR, LABEL_SEPARATOR};
+use super::metadata::{MetadataStore, SessionMetadata};
+use super::relocate::replace_path_prefix;
+use super::rename::{rewrite_this_session, validate_session_name};
+use crate::error::VsmRuntimeFault;
+
+/// Name of the manifest inside the archive.
+const MANIFEST_FILE: &str = "manifest.toml";
+
+/// Directory holding the session files inside the archive.
+const SESSIONS_DIR: &str = "sessions";
+
+/// Version of the bundle layout, bumped on incompatible changes.
+const BUNDLE_VERSION: u32 = 1;
+
+/// A session as it is described in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct BundledSession {
+    /// Session name, grouped and labelled as on the exporting machine
+    name: String,
+    /// Hex encoded sha256 of the session file
+    sha256: String,
+    /// Hex encoded sha256 of the companion file, if the session had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    companion_sha256: Option<String>,
+    /// The metadata of the session, if it had any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<SessionMetadata>,
+}
+
+/// Describes what a bundle holds and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct BundleManifest {
+    /// Version of the bundle layout
+    version: u32,
+    /// When the bundle was written, in seconds since the unix epoch
+    created_at: u64,
+    /// The `$HOME` of the user that exported the sessions
+    source_home: String,
+    /// The sessions in the bundle
+    #[serde(default)]
+    sessions: Vec<BundledSession>,
+}
+
+/// What to do with a bundled session whose name is already taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClashPolicy {
+    /// Import it under a free name
+    Rename,
+    /// Leave the existing session alone
+    Skip,
+    /// Replace the existing session
+    Overwrite,
+}
+
+impl ClashPolicy {
+    /// Names of all policies, as accepted on the command line.
+    pub const NAMES: [&'static str; 3] = ["rename", "skip", "overwrite"];
+}
+
+impl FromStr for ClashPolicy {
+    type Err = VsmRuntimeFault;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rename" => Ok(Self::Rename),
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            _ => Err(VsmRuntimeFault::Bundle {
+                msg: format!("Unknown clash policy '{}'", s),
+            }),
+        }
+    }
+}
+
+/// A bundle read into memory, with every file checked against the manifest.
+#[derive(Debug, Getters)]
+pub struct SessionBundle {
+    /// The manifest of the bundle
+    manifest: BundleManifest,
+    /// Contents of the archived files, keyed by their path in the archive
+    #[getter(skip)]
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl SessionBundle {
+    /// Writes sessions, their companion files and metadata into a bundle.
+    /// Companion files given as sessions of their own are only bundled along
+    /// with their session.
+    ///
+    /// # Arguments
+    ///     * archive Path of the bundle to write.
+    ///     * sessions Paths to the session files.
+    ///     * dirs The session directories that name the sessions.
+    ///     * metadata The metadata of the sessions.
+    ///     * home The users home directory, recorded in the manifest.
+    ///     * now Current time, in seconds since the unix epoch.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Bundle if a session can not be read, is given
+    ///       twice, or the archive can not be written.
+    pub fn export(
+        archive: &Path,
+        sessions: &[PathBuf],
+        dirs: &SessionDirs,
+        metadata: &MetadataStore,
+        home: &str,
+        now: u64,
+    ) -> Result<BundleManifest, VsmRuntimeFault> {
+        let fault = |path: &Path, e: String| VsmRuntimeFault::Bundle {
+            msg: format!("{} => {}", path.display(), e),
+        };
+        let mut files: Vec<(String, Vec<u8>)> = vec![];
+        let mut bundled: Vec<BundledSession> = vec![];
+        for session in sessions.iter().filter(|session| !is_companion(session)) {
+            let name = dirs.name_of(session);
+            if bundled.iter().any(|known| known.name == name) {
+                return Err(fault(session, format!("'{}' is given twice", name)));
+            }
+            let contents = fs::read(session).map_err(|e| fault(session, e.to_string()))?;
+            let companion = companion_path(session);
+            let companion_sha256 = if companion.exists() {
+                let extra = fs::read(&companion).map_err(|e| fault(&companion, e.to_string()))?;
+                let hash = sha256(&extra);
+                files.push((companion_file(&name), extra));
+                Some(hash)
+            } else {
+                None
+            };
+            bundled.push(BundledSession {
+                sha256: sha256(&contents),
+                companion_sha256,
+                metadata: metadata.get(&dirs.key_of(session)).cloned(),
+                name: name.clone(),
+            });
+            files.push((session_file(&name), contents));
+        }
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            created_at: now,
+            source_home: home.to_owned(),
+            sessions: bundled,
+        };
+        let manifest_contents =
+            toml::to_string(&manifest).map_err(|e| fault(archive, e.to_string()))?;
+
+        let file = File::create(archive).map_err(|e| fault(archive, e.to_string()))?;
+        let mut builder = Builder::new(file);
+        let entries = std::iter::once((MANIFEST_FILE.to_owned(), manifest_contents.into_bytes()))
+            .chain(files);
+        for (path, contents) in entries {
+            let mut header = Header::new_gnu();
+            header.set_size(contents.len().try_into().unwrap_or(u64::MAX));
+            header.set_mode(0o644);
+            header.set_mtime(now);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, &path, contents.as_slice())
+                .map_err(|e| fault(archive, e.to_string()))?;
+        }
+        builder
+            .into_inner()
+            .map_err(|e| fault(archive, e.to_string()))?;
+        Ok(manifest)
+    }
+
+    /// Reads a bundle, checking every session against the manifest.
+    ///
+    /// # Arguments
+    ///     * archive Path of the bundle.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Bundle if the archive can not be read, has no
+    ///       manifest, holds an invalid name, or a file is missing or does
+    ///       not match its hash.
+    pub fn read(archive: &Path) -> Result<Self, VsmRuntimeFault> {
+        let fault = |e: String| VsmRuntimeFault::Bundle {
+            msg: format!("{} => {}", archive.display(), e),
+        };
+        let file = File::open(archive).map_err(|e| fault(e.to_string()))?;
+        let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        for entry in Archive::new(file)
+            .entries()
+            .map_err(|e| fault(e.to_string()))?
+        {
+            let mut entry = entry.map_err(|e| fault(e.to_string()))?;
+            let path = entry
+                .path()
+                .map_err(|e| fault(e.to_string()))?
+                .to_string_lossy()
+                .to_string();
+            let mut contents: Vec<u8> = vec![];
+            entry
+                .read_to_end(&mut contents)
+                .map_err(|e| fault(e.to_string()))?;
+            files.insert(path, contents);
+        }
+
+        let manifest_contents = files
+            .get(MANIFEST_FILE)
+            .ok_or_else(|| fault(format!("it has no {}", MANIFEST_FILE)))?;
+        let manifest: BundleManifest = toml::from_str(&String::from_utf8_lossy(manifest_contents))
+            .map_err(|e| fault(e.to_string()))?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err(fault(format!(
+                "bundle version {} is newer than this vsm understands",
+                manifest.version
+            )));
+        }
+        let bundle = Self { manifest, files };
+        for session in &bundle.manifest.sessions {
+            validate_bundled_name(&session.name).map_err(|e| fault(e.to_string()))?;
+            let expected = std::iter::once((session_file(&session.name), Some(&session.sha256)))
+                .chain(std::iter::once((
+                    companion_file(&session.name),
+                    session.companion_sha256.as_ref(),
+                )));
+            for (path, hash) in expected {
+                let Some(hash) = hash else {
+                    continue;
+                };
+                let contents = bundle
+                    .files
+                    .get(&path)
+                    .ok_or_else(|| fault(format!("{} is missing", path)))?;
+                if sha256(contents) != *hash {
+                    return Err(fault(format!("{} does not match its hash", path)));
+                }
+            }
+        }
+        Ok(bundle)
+    }
+
+    /// Writes a bundled session to a path. Nothing is ever overwritten, a
+    /// session in the way has to be moved out of it first.
+    ///
+    /// # Arguments
+    ///     * session The bundled session.
+    ///     * target Path of the session file to write, see
+    ///       `SessionDirs::path_for`.
+    ///     * home The local home directory, paths under the source `$HOME` are
+    ///       rewritten to it when given.
+    ///
+    /// # Returns
+    ///     * The path of the imported session.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Bundle if the target, or the path its companion
+    ///       would be written to, is taken, or the files can not be written.
+    pub fn install(
+        &self,
+        session: &BundledSession,
+        target: &Path,
+        home: Option<&str>,
+    ) -> Result<PathBuf, VsmRuntimeFault> {
+        let fault = |path: &Path, e: String| VsmRuntimeFault::Bundle {
+            msg: format!("{} => {}", path.display(), e),
+        };
+        let companion = companion_path(target);
+        if target.exists() {
+            return Err(fault(target, "it already exists".to_owned()));
+        }
+        if session.companion_sha256.is_some() && companion.exists() {
+            return Err(fault(
+                &companion,
+                "it is in the way of the imported companion".to_owned(),
+            ));
+        }
+        let stem = |path: &Path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let old_stem = stem(Path::new(&session_file(&session.name)));
+        let new_stem = stem(target);
+        let rewrite = |path: &str| {
+            let contents = String::from_utf8_lossy(
+                self.files.get(path).map(Vec::as_slice).unwrap_or_default(),
+            )
+            .to_string();
+            let renamed = rewrite_this_session(&contents, &old_stem, &new_stem);
+            match home {
+                Some(local) => replace_path_prefix(&renamed, &self.manifest.source_home, local),
+                None => renamed,
+            }
+        };
+
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).map_err(|e| fault(dir, e.to_string()))?;
+        }
+        fs::write(target, rewrite(&session_file(&session.name)))
+            .map_err(|e| fault(target, e.to_string()))?;
+        if session.companion_sha256.is_some() {
+            fs::write(&companion, rewrite(&companion_file(&session.name)))
+                .map_err(|e| fault(&companion, e.to_string()))?;
+        }
+        Ok(target.to_path_buf())
+    }
+}
+
+/// Hex encoded sha256 of some bytes.
+fn sha256(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Checks the name of a bundled session, which is grouped and labelled the
+/// same way as the names `SessionDirs` gives.
+fn validate_bundled_name(name: &str) -> Result<(), VsmRuntimeFault> {
+    let stem = match name.split_once(LABEL_SEPARATOR) {
+        Some((label, stem)) => {
+            validate_session_name(label)?;
+            stem
+        }
+        None => name,
+    };
+    stem.split(GROUP_SEPARATOR)
+        .try_for_each(validate_session_name)
+}
+
+/// Where the session file of a bundled session is kept inside the archive.
+fn session_file(name: &str) -> String {
+    format!("{}/{}.vim", SESSIONS_DIR, name)
+}
+
+/// Where the companion file of a bundled session is kept inside the archive.
+fn companion_file(name: &str) -> String {
+    companion_path(Path::new(&session_file(name)))
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{replace_path_prefix, BundledSession, SessionBundle};
+    use crate::session::{MetadataStore, SessionDirs};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_replace_path_prefix_respects_boundaries() {
+        let session = "cd /home/al/api\nbadd +1 /home/alice/x.rs\nedit /home/al\n";
+        assert_eq!(
+            replace_path_prefix(session, "/home/al", "/Users/al"),
+            "cd /Users/al/api\nbadd +1 /home/alice/x.rs\nedit /Users/al\n"
+        );
+    }
+
+    #[test]
+    fn test_export_and_install_round_trip() {
+        let sandbox = test_dir("bundle");
+        let root = sandbox.path();
+        let sessions = root.join("sessions");
+        fs::create_dir_all(sessions.join("work")).expect("sandbox is created");
+        let session = sessions.join("api.vim");
+        fs::write(
+            &session,
+            "cd /home/al/api\nlet v:this_session = \"/home/al/s/api.vim\"\n",
+        )
+        .expect("session is written");
+        let companion = sessions.join("apix.vim");
+        fs::write(&companion, "\" extra").expect("companion is written");
+        // tmx.vim is a session of its own, not the companion of tm.vim
+        let others = ["work/api.vim", "tm.vim", "tmx.vim"].map(|file| sessions.join(file));
+        for other in &others {
+            fs::write(other, "let SessionLoad = 1\n").expect("session is written");
+        }
+        let mut metadata = MetadataStore::load(&root.join("metadata.toml")).expect("empty store");
+        metadata.entry("api").set_description("REST gateway");
+        metadata.entry("work/api").set_description("Work gateway");
+
+        let archive = root.join("bundle.tar");
+        let mut chosen = vec![session, companion];
+        chosen.extend(others);
+        let dirs = SessionDirs::from_list(&sessions.to_string_lossy());
+        SessionBundle::export(&archive, &chosen, &dirs, &metadata, "/home/al", 10)
+            .expect("bundle is written");
+        let bundle = SessionBundle::read(&archive).expect("bundle is read");
+        let bundled = bundle.manifest().sessions();
+        assert_eq!(
+            bundled.iter().map(BundledSession::name).collect::<Vec<_>>(),
+            vec!["api", "work/api", "tm", "tmx"]
+        );
+        let descriptions: Vec<Option<String>> = bundled
+            .iter()
+            .take(2)
+            .map(|bundled| {
+                bundled
+                    .metadata()
+                    .as_ref()
+                    .and_then(|m| m.description().clone())
+            })
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                Some("REST gateway".to_owned()),
+                Some("Work gateway".to_owned())
+            ]
+        );
+
+        let api = bundled.first().expect("api is bundled");
+        let target = root.join("imported").join("api-2.vim");
+        let installed = bundle
+            .install(api, &target, Some("/Users/al"))
+            .expect("session is imported");
+        assert_eq!(
+            fs::read_to_string(installed).expect("session is written"),
+            "cd /Users/al/api\nlet v:this_session = \"/Users/al/s/api-2.vim\"\n"
+        );
+        assert!(root.join("imported").join("api-2x.vim").exists());
+        bundle
+            .install(api, &target, None)
+            .expect_err("an existing session is not overwritten");
+    }
+}
@@ -0,0 +1,286 @@
+//! A read and remove interface over a directory of session files.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::dirs::SessionDirs;
+use super::health::SessionHealth;
+use super::history::OpenHistory;
+use super::metadata::MetadataStore;
+use super::record::SessionRecord;
+use super::rename::move_session;
+use super::trash::Trash;
+use crate::config::{TomlConfigFile, ENVIRONMENT};
+use crate::error::VsmRuntimeFault;
+use crate::utils::{match_sessions, resolve_session_name, FilesystemManager, SessionMatch};
+
+/// Discovers the session files kept in a session directory, the same way the
+/// `vsm` binary does.
+#[derive(Debug)]
+pub struct SessionCatalog {
+    /// Every session directory, the primary one first
+    dirs: SessionDirs,
+    /// Directory holding the session metadata
+    data_dir: PathBuf,
+    /// Home directory `~` in sessions stands for
+    home: String,
+}
+
+impl SessionCatalog {
+    /// Builds a catalog from the environment, the way the `vsm` binary does:
+    /// the sessions in `$VIM_SESSIONS`, falling back to
+    /// `~/.config/vim_sessions` if the variable is not defined, the session
+    /// directories listed in the config.toml, and the project-local sessions
+    /// around the working directory. Metadata is kept in
+    /// `~/.local/share/vsm`.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::TomlConfigFileRead if the config.toml exists but
+    ///       can not be read.
+    pub fn from_env() -> Result<Self, VsmRuntimeFault> {
+        let fs = FilesystemManager::new(ENVIRONMENT.path().vsm_config_file());
+        let mut catalog = Self::new(
+            ENVIRONMENT.var().vim_sessions(),
+            ENVIRONMENT.path().vsm_data_dir(),
+            ENVIRONMENT.var().home(),
+        );
+        // Without a config.toml the defaults still discover project sessions
+        let config = if fs.config_file_exists() {
+            fs.read_config()?
+        } else {
+            TomlConfigFile::default()
+        };
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        catalog.dirs = catalog.dirs.configure(&config, &catalog.home, &cwd);
+        Ok(catalog)
+    }
+
+    /// Builds a catalog of the session files in a directory, nothing is read
+    /// from the environment.
+    ///
+    /// # Arguments
+    ///     * session_dir Absolute path to the session directory, or a colon
+    ///       separated list of them.
+    ///     * data_dir Absolute path to the data directory, which holds the
+    ///       metadata, history and trash of the sessions.
+    ///     * home The home directory ~ in sessions stands for.
+    #[must_use]
+    pub fn new(session_dir: &str, data_dir: &str, home: &str) -> Self {
+        Self {
+            dirs: SessionDirs::from_list(session_dir),
+            data_dir: PathBuf::from(data_dir),
+            home: home.to_owned(),
+        }
+    }
+
+    /// Absolute path to the primary session directory of this catalog.
+    #[must_use]
+    pub fn session_dir(&self) -> &str {
+        self.dirs
+            .primary()
+            .and_then(|dir| dir.path().to_str())
+            .unwrap_or_default()
+    }
+
+    /// Every session directory this catalog reads, the primary one first.
+    #[must_use]
+    const fn session_dirs(&self) -> &SessionDirs {
+        &self.dirs
+    }
+
+    /// Reads the descriptions, notes and tags of the sessions.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Metadata if the store can not be read.
+    fn metadata(&self) -> Result<MetadataStore, VsmRuntimeFault> {
+        MetadataStore::load_from_dir(&self.data_dir)
+    }
+
+    /// Reads the open history of the sessions.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::History if the history can not be read.
+    fn history(&self) -> Result<OpenHistory, VsmRuntimeFault> {
+        OpenHistory::load_from_dir(&self.data_dir)
+    }
+
+    /// Collects the paths of all session files, sorted by path. The session
+    /// directory is created if it does not exist yet.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the directory can not be read.
+    pub fn paths(&self) -> Result<Vec<PathBuf>, VsmRuntimeFault> {
+        match self.dirs.load() {
+            Ok(maybe_empty_sessions) => Ok(maybe_empty_sessions.unwrap_or_default()),
+            Err(e) => Err(VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", self.session_dir(), e),
+            }),
+        }
+    }
+
+    /// Collects the names of all sessions, the file name without `.vim`
+    /// prefixed by the label of its directory outside the primary one.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the directory can not be read.
+    pub fn names(&self) -> Result<Vec<String>, VsmRuntimeFault> {
+        Ok(self.session_dirs().names(&self.paths()?))
+    }
+
+    /// Collects a record of every session.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the directory, or the metadata
+    ///       of a session file can not be read.
+    pub fn sessions(&self) -> Result<Vec<SessionRecord>, VsmRuntimeFault> {
+        self.paths()?
+            .iter()
+            .map(|session| {
+                SessionRecord::from_path_in(session, self.session_dirs()).map_err(|e| {
+                    VsmRuntimeFault::SessionFileRead {
+                        msg: format!("{} => {}", session.display(), e),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Looks up a session by its exact name.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the catalog can not be read.
+    pub fn find(&self, name: &str) -> Result<Option<SessionRecord>, VsmRuntimeFault> {
+        Ok(self
+            .sessions()?
+            .into_iter()
+            .find(|session| session.name() == name))
+    }
+
+    /// Resolves a name the way `vsm open <name>` does: exact name first, then
+    /// a unique prefix, then a fuzzy match.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the catalog can not be read.
+    pub fn resolve(&self, query: &str) -> Result<SessionMatch, VsmRuntimeFault> {
+        Ok(resolve_session_name(query, &self.names()?))
+    }
+
+    /// Collects the sessions matched by a name, or a shell-style glob pattern
+    /// such as `tmp-*`. Project sessions are only matched by their exact
+    /// name, a glob never reaches into a project.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the catalog can not be read.
+    ///     * VsmRuntimeFault::SessionResolution if the pattern is malformed.
+    pub fn matching(&self, pattern: &str) -> Result<Vec<SessionRecord>, VsmRuntimeFault> {
+        let matched = match_sessions(pattern, &self.names()?)?;
+        Ok(self
+            .sessions()?
+            .into_iter()
+            .filter(|session| matched.contains(session.name()))
+            .filter(|session| !session.project_local() || session.name() == pattern)
+            .collect())
+    }
+
+    /// Checks every session against the filesystem, see `vsm check`.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the directory can not be read.
+    pub fn check(&self) -> Result<Vec<SessionHealth>, VsmRuntimeFault> {
+        Ok(self
+            .paths()?
+            .iter()
+            .map(|session| {
+                SessionHealth::check(session, &self.home)
+                    .with_name(&self.session_dirs().name_of(session))
+            })
+            .collect())
+    }
+
+    /// Renames a session, moving its companion `x.vim` file along, see
+    /// `vsm rename`. A grouped name moves the session into that group, and
+    /// with `force` a session that already has the name is moved to the
+    /// trash.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionName if the name is invalid.
+    ///     * VsmRuntimeFault::SessionRename if the name is taken
+    ///       without force, or the files can not be moved.
+    ///     * VsmRuntimeFault::Trash if the session in the way can not be
+    ///       trashed.
+    ///     * VsmRuntimeFault::SessionFileRead if the renamed session can not
+    ///       be inspected.
+    ///     * VsmRuntimeFault::Metadata if the metadata can not be moved along.
+    ///     * VsmRuntimeFault::History if the history can not be moved along.
+    pub fn rename(
+        &self,
+        session: &SessionRecord,
+        new_name: &str,
+        force: bool,
+    ) -> Result<SessionRecord, VsmRuntimeFault> {
+        let target = self.session_dirs().path_for(new_name)?;
+        if force && target.exists() && target != *session.path() {
+            self.trash(
+                &target,
+                &self.dirs.name_of(&target),
+                &self.dirs.key_of(&target),
+            )?;
+        }
+        let renamed = move_session(session.path(), &target)?;
+        let record = SessionRecord::from_path_in(&renamed, self.session_dirs()).map_err(|e| {
+            VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", renamed.display(), e),
+            }
+        })?;
+        let mut metadata = self.metadata()?;
+        metadata.rename(session.key(), record.key());
+        metadata.save()?;
+        let mut history = self.history()?;
+        history.rename(session.key(), record.key());
+        history.save()?;
+        Ok(record)
+    }
+
+    /// Moves a session file and its companion to the trash, along with its
+    /// metadata and history, the way `vsm remove` does. `vsm restore` brings
+    /// it back.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if the files can not be moved.
+    ///     * VsmRuntimeFault::Metadata if the metadata can not be updated.
+    ///     * VsmRuntimeFault::History if the history can not be updated.
+    pub fn remove(&self, session: &SessionRecord) -> Result<(), VsmRuntimeFault> {
+        self.trash(session.path(), session.name(), session.key())
+    }
+
+    /// Moves a session file and its companion to the trash, along with its
+    /// metadata and history.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if the files can not be moved.
+    ///     * VsmRuntimeFault::Metadata if the metadata can not be updated.
+    ///     * VsmRuntimeFault::History if the history can not be updated.
+    fn trash(&self, session: &Path, name: &str, key: &str) -> Result<(), VsmRuntimeFault> {
+        let mut metadata = self.metadata()?;
+        let mut history = self.history()?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Trash::in_data_dir(&self.data_dir).put(
+            session,
+            name,
+            metadata.get(key).cloned(),
+            history.get(key).cloned(),
+            now,
+        )?;
+        if metadata.get(key).is_some() {
+            metadata.remove(key);
+            metadata.save()?;
+        }
+        if history.get(key).is_some() {
+            history.remove(key);
+            history.save()?;
+        }
+        Ok(())
+    }
+}
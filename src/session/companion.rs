@@ -0,0 +1,95 @@
+//! The extra file vim sources after a session, `<name>x.vim`. A file of that
+//! name is only a companion when the session it belongs to exists next to it
+//! and the file was not written by `:mksession` itself, otherwise it is a
+//! session of its own such as `bx.vim` next to `b.vim`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Suffix vim appends to the session name for the extra file it sources after
+/// the session, `<name>x.vim`.
+const COMPANION_SUFFIX: &str = "x.vim";
+
+/// Every session `:mksession` writes, for vim and neovim alike, holds this
+/// line.
+const SESSION_MARKER: &str = "let SessionLoad = 1";
+
+/// Path a companion of the session would have, whether or not it exists.
+///
+/// # Arguments
+///     * session Path to the session file.
+#[must_use]
+pub fn companion_path(session: &Path) -> PathBuf {
+    let stem = session
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    session.with_file_name(format!("{}{}", stem, COMPANION_SUFFIX))
+}
+
+/// The companion of a session, if it has one.
+///
+/// # Arguments
+///     * session Path to the session file.
+#[must_use]
+pub fn companion_of(session: &Path) -> Option<PathBuf> {
+    let companion = companion_path(session);
+    (session.is_file() && is_extra_script(&companion)).then_some(companion)
+}
+
+/// Returns true if the file is the companion of a session next to it, and
+/// not a session of its own.
+///
+/// # Arguments
+///     * path Path to a `.vim` file.
+#[must_use]
+pub fn is_companion(path: &Path) -> bool {
+    let Some(session) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(COMPANION_SUFFIX))
+        .filter(|stem| !stem.is_empty())
+        .map(|stem| path.with_file_name(format!("{}.vim", stem)))
+    else {
+        return false;
+    };
+    session.is_file() && is_extra_script(path)
+}
+
+/// Returns true if the file exists and was not written by `:mksession`.
+fn is_extra_script(path: &Path) -> bool {
+    path.is_file()
+        && !fs::read_to_string(path).is_ok_and(|contents| contents.contains(SESSION_MARKER))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{companion_of, is_companion};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_sessions_ending_in_x_are_not_companions() {
+        let dir = test_dir("companion");
+        let root = dir.path();
+        let session = "let SessionLoad = 1\n";
+        fs::write(root.join("api.vim"), session).expect("session is written");
+        fs::write(root.join("apix.vim"), "\" extra").expect("companion is written");
+        fs::write(root.join("b.vim"), session).expect("session is written");
+        fs::write(root.join("bx.vim"), session).expect("session is written");
+        fs::write(root.join("linux.vim"), session).expect("session is written");
+
+        assert!(is_companion(&root.join("apix.vim")));
+        assert_eq!(
+            companion_of(&root.join("api.vim")),
+            Some(root.join("apix.vim"))
+        );
+        assert!(!is_companion(&root.join("bx.vim")));
+        assert_eq!(companion_of(&root.join("b.vim")), None);
+        // `linu.vim` does not exist, so there is nothing to be a companion of
+        assert!(!is_companion(&root.join("linux.vim")));
+    }
+}
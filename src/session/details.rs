@@ -0,0 +1,156 @@
+//! Everything that can be learned about a single session, from its file on
+//! disk and from its contents.
+
+use std::path::{Path, PathBuf};
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use super::dirs::SessionDirs;
+use super::parser::{Buffer, Editor, SessionFile, TabPage, UnrecognizedLine};
+use super::record::SessionRecord;
+use crate::error::VsmRuntimeFault;
+
+/// A buffer of the session, and whether its file is still around.
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct BufferStatus {
+    /// The buffer as written in the session
+    #[serde(flatten)]
+    buffer: Buffer,
+    /// The file the buffer names, None for terminals
+    resolved: Option<PathBuf>,
+    /// True if the file exists, terminals always exist
+    exists: bool,
+}
+
+/// A detailed view of a session, used by `vsm show`.
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct SessionDetails {
+    /// Name, path, size and mtime of the session file
+    #[serde(flatten)]
+    record: SessionRecord,
+    /// The editor that most likely wrote the session
+    editor: Editor,
+    /// Global working directory, as written
+    cwd: Option<String>,
+    /// True if the global working directory exists
+    cwd_exists: bool,
+    /// The tab page that was current, starting at 1
+    active_tab: Option<usize>,
+    /// Tab pages and their windows
+    tabs: Vec<TabPage>,
+    /// The buffer list, with existence checks
+    buffers: Vec<BufferStatus>,
+    /// Lines of the session that were not understood
+    unrecognized: Vec<UnrecognizedLine>,
+}
+
+impl SessionDetails {
+    /// Reads and parses a session file, and checks which of the files it
+    /// refers to still exist.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///     * dirs The session directories, which name the session.
+    ///     * home The home directory to expand `~` to.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the file can not be read.
+    pub fn inspect(
+        session: &Path,
+        dirs: &SessionDirs,
+        home: &str,
+    ) -> Result<Self, VsmRuntimeFault> {
+        let record = SessionRecord::from_path_in(session, dirs).map_err(|e| {
+            VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", session.display(), e),
+            }
+        })?;
+        let file = SessionFile::from_path(session)?;
+        Ok(Self::from_parts(record, &file, home))
+    }
+
+    /// Combines a record with the parsed contents of its session file.
+    ///
+    /// # Arguments
+    ///     * record The session file on disk.
+    ///     * file The parsed contents of the session file.
+    ///     * home The home directory to expand `~` to.
+    #[must_use]
+    pub fn from_parts(record: SessionRecord, file: &SessionFile, home: &str) -> Self {
+        let buffers = file
+            .buffers()
+            .iter()
+            .map(|buffer| {
+                let resolved =
+                    (!*buffer.terminal()).then(|| file.resolve_path(buffer.path(), home));
+                BufferStatus {
+                    exists: resolved.as_ref().is_none_or(|path| path.exists()),
+                    resolved,
+                    buffer: buffer.clone(),
+                }
+            })
+            .collect();
+        Self {
+            record,
+            editor: *file.editor(),
+            cwd: file.cwd().clone(),
+            cwd_exists: file
+                .cwd()
+                .as_deref()
+                .is_none_or(|cwd| file.resolve_path(cwd, home).is_dir()),
+            active_tab: *file.active_tab(),
+            tabs: file.tabs().clone(),
+            buffers,
+            unrecognized: file.unrecognized().clone(),
+        }
+    }
+
+    /// Number of buffers whose file no longer exists.
+    #[must_use]
+    pub fn missing_buffers(&self) -> usize {
+        self.buffers.iter().filter(|buffer| !buffer.exists).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::SessionDetails;
+    use crate::session::SessionDirs;
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_inspect_checks_buffer_existence() {
+        let sandbox = test_dir("details");
+        let root = sandbox.path();
+        fs::write(root.join("present.rs"), "").expect("Failed to write a buffer");
+        fs::create_dir_all(root.join("work")).expect("Failed to create a group");
+        let session = root.join("work").join("api.vim");
+        fs::write(
+            &session,
+            format!(
+                "cd {}\nbadd +3 present.rs\nbadd +1 gone.rs\nbadd +1 ~/elsewhere.rs\nedit present.rs\n",
+                root.display()
+            ),
+        )
+        .expect("Failed to write the session");
+
+        let dirs = SessionDirs::from_list(&root.to_string_lossy());
+        let details =
+            SessionDetails::inspect(&session, &dirs, &root.join("home").to_string_lossy())
+                .expect("Failed to inspect the session");
+        let exists: Vec<bool> = details.buffers().iter().map(|b| *b.exists()).collect();
+        assert_eq!(exists, vec![true, false, false]);
+        assert_eq!(details.missing_buffers(), 2);
+        assert!(*details.cwd_exists());
+        assert_eq!(
+            details.buffers().get(2).and_then(|b| b.resolved().clone()),
+            Some(root.join("home").join("elsewhere.rs"))
+        );
+        assert_eq!(details.record().name(), "work/api");
+    }
+}
@@ -0,0 +1,538 @@
+//! The directories sessions are read from. `$VIM_SESSIONS` may list several
+//! of them separated by colons, and the config.toml can add more. Every
+//! directory carries a label, sessions outside the first directory are named
+//! `label:name` so equal file names in different directories stay apart.
+//! Sub-directories are scanned as well, a session in one is named by its
+//! relative path, `work/api`, and the sub-directory forms its group.
+//! Projects holding a `Session.vim` join as directories of their own.
+//!
+//! The metadata and the open history of a session are kept under its name,
+//! only project sessions are kept under the canonical path of their
+//! `Session.vim`, their label depends on which projects were discovered.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use derive_getters::Getters;
+use log::debug;
+
+use super::companion::is_companion;
+use super::project::{discover_in_workspaces, discover_upwards, PROJECT_SESSION_FILE};
+use super::rename::validate_session_name;
+use crate::config::{SessionDirConfig, TomlConfigFile};
+use crate::error::VsmRuntimeFault;
+
+/// Separates the entries of a `$VIM_SESSIONS` list.
+const LIST_SEPARATOR: char = ':';
+
+/// Separates a label from the path in a `$VIM_SESSIONS` entry, `team=/path`.
+const LABEL_ASSIGNMENT: char = '=';
+
+/// Separates the label from the session name, `team:api`.
+pub const LABEL_SEPARATOR: char = ':';
+
+/// Separates the group from the session name, `work/api`.
+pub const GROUP_SEPARATOR: char = '/';
+
+/// How many levels of sub-directories are scanned when the config.toml does
+/// not set `scan_depth`.
+pub const DEFAULT_SCAN_DEPTH: usize = 3;
+
+/// The group of a session, the part of its name before the last `/`.
+///
+/// # Arguments
+///     * name Session name, such as `work/api` or `team:work/api`.
+#[must_use]
+#[cfg(feature = "cli")]
+pub fn session_group(name: &str) -> Option<&str> {
+    name.rsplit_once(GROUP_SEPARATOR).map(|(group, _)| group)
+}
+
+/// Picks a name that is not taken yet, by appending `-2`, `-3` and so on.
+///
+/// # Arguments
+///     * name The wanted name.
+///     * taken Returns true if a name is in use.
+#[must_use]
+pub fn free_name<F>(name: &str, taken: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    (2..)
+        .map(|suffix: u32| format!("{}-{}", name, suffix))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| name.to_owned())
+}
+
+/// Returns true if a label can name a session directory, it is part of the
+/// session names so it can not hold a separator.
+fn is_label(label: &str) -> bool {
+    !label.is_empty() && !label.contains([LABEL_SEPARATOR, GROUP_SEPARATOR])
+}
+
+/// A directory holding session files.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct SessionDir {
+    /// Namespace label, shown as the source of its sessions
+    label: String,
+    /// Path to the directory
+    path: PathBuf,
+    /// A project root, only its `Session.vim` is read
+    project: bool,
+}
+
+impl SessionDir {
+    /// Builds a session directory. Without a usable label the name of the
+    /// directory is used.
+    ///
+    /// # Arguments
+    ///     * label The namespace label, may be empty.
+    ///     * path Path to the directory.
+    #[must_use]
+    pub fn new(label: &str, path: &Path) -> Self {
+        let label = if is_label(label) {
+            label.to_owned()
+        } else {
+            path.file_name().map_or_else(
+                || "sessions".to_owned(),
+                |name| name.to_string_lossy().to_string(),
+            )
+        };
+        Self {
+            label,
+            path: path.to_path_buf(),
+            project: false,
+        }
+    }
+
+    /// Builds the directory of a project holding a `Session.vim`, labelled
+    /// with the name of the project.
+    ///
+    /// # Arguments
+    ///     * path Path to the project root.
+    #[must_use]
+    pub fn for_project(path: &Path) -> Self {
+        Self {
+            project: true,
+            ..Self::new("", path)
+        }
+    }
+}
+
+/// The session directories in the order they are searched. The first one is
+/// where new sessions go, its sessions are named without a label.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct SessionDirs {
+    /// Every directory, the first one is the primary
+    dirs: Vec<SessionDir>,
+    /// Levels of sub-directories scanned below every directory
+    depth: usize,
+}
+
+impl SessionDirs {
+    /// Parses a `$VIM_SESSIONS` value, a colon separated list of directories.
+    /// An entry may carry its label as `label=/path`, an entry whose part
+    /// before the `=` is no label, such as `/srv/a=b`, is a path as a whole.
+    ///
+    /// # Arguments
+    ///     * list The directories, a single path is a list of one.
+    #[must_use]
+    pub fn from_list(list: &str) -> Self {
+        let mut dirs = Self {
+            dirs: vec![],
+            depth: DEFAULT_SCAN_DEPTH,
+        };
+        for entry in list.split(LIST_SEPARATOR).filter(|entry| !entry.is_empty()) {
+            let dir = match entry.split_once(LABEL_ASSIGNMENT) {
+                Some((label, path)) if is_label(label) && !path.is_empty() => {
+                    SessionDir::new(label, Path::new(path))
+                }
+                _ => SessionDir::new("", Path::new(entry)),
+            };
+            dirs.push(dir);
+        }
+        if dirs.dirs.is_empty() {
+            dirs.push(SessionDir::new("", Path::new(list)));
+        }
+        dirs
+    }
+
+    /// Appends directories, such as the ones listed in the config.toml.
+    /// Directories already present are skipped, and taken labels get a
+    /// number appended.
+    #[must_use]
+    pub fn with_dirs<I>(mut self, dirs: I) -> Self
+    where
+        I: IntoIterator<Item = SessionDir>,
+    {
+        for dir in dirs {
+            self.push(dir);
+        }
+        self
+    }
+
+    /// Sets how many levels of sub-directories are scanned, 0 only reads the
+    /// directories themselves.
+    #[must_use]
+    pub const fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Adds the session directories listed in the config.toml, applies its
+    /// scan depth, and adds the projects holding a `Session.vim` around the
+    /// working directory and below the workspace roots.
+    ///
+    /// # Arguments
+    ///     * config The configuration read from disk.
+    ///     * home The home directory a leading `~/` stands for.
+    ///     * cwd The working directory project discovery starts from.
+    #[must_use]
+    pub fn configure(self, config: &TomlConfigFile, home: &str, cwd: &Path) -> Self {
+        let expand = |path: &str| {
+            path.strip_prefix("~/").map_or_else(
+                || PathBuf::from(path),
+                |relative| Path::new(home).join(relative),
+            )
+        };
+        let mut dirs: Vec<SessionDir> = config
+            .session_dirs()
+            .iter()
+            .map(|dir: &SessionDirConfig| SessionDir::new(dir.label(), &expand(dir.path())))
+            .collect();
+        let projects = config.projects();
+        let mut found = if *projects.discover() {
+            discover_upwards(cwd)
+        } else {
+            vec![]
+        };
+        let workspaces: Vec<PathBuf> = projects
+            .workspaces()
+            .iter()
+            .map(|root| expand(root))
+            .collect();
+        found.extend(discover_in_workspaces(&workspaces));
+        debug!("Found {} project-local session(s)", found.len());
+        dirs.extend(found.iter().map(|project| SessionDir::for_project(project)));
+
+        let depth = config.scan_depth().unwrap_or(DEFAULT_SCAN_DEPTH);
+        self.with_dirs(dirs).with_depth(depth)
+    }
+
+    /// Adds a directory, unless it is already present.
+    fn push(&mut self, mut dir: SessionDir) {
+        let trimmed = |path: &Path| path.to_string_lossy().trim_end_matches('/').to_owned();
+        let same = |known: &Path| {
+            trimmed(known) == trimmed(&dir.path)
+                || fs::canonicalize(known).is_ok_and(|canonical| {
+                    fs::canonicalize(&dir.path).is_ok_and(|path| canonical == path)
+                })
+        };
+        if self.dirs.iter().any(|known| same(&known.path)) {
+            return;
+        }
+        let taken = |label: &str| self.dirs.iter().any(|known| known.label == label);
+        if taken(&dir.label) {
+            dir.label = free_name(&dir.label, taken);
+        }
+        self.dirs.push(dir);
+    }
+
+    /// The directory new sessions go to.
+    #[must_use]
+    pub fn primary(&self) -> Option<&SessionDir> {
+        self.dirs.first()
+    }
+
+    /// The directory a session file is kept in. When directories are nested
+    /// the innermost one holding the session wins.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    #[must_use]
+    pub fn source(&self, session: &Path) -> Option<&SessionDir> {
+        let parent = session.parent()?;
+        self.dirs
+            .iter()
+            .filter(|dir| parent.starts_with(&dir.path))
+            .max_by_key(|dir| dir.path.components().count())
+    }
+
+    /// Returns true if the session is kept at a project root.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    #[must_use]
+    pub fn is_project_local(&self, session: &Path) -> bool {
+        self.source(session).is_some_and(|dir| dir.project)
+    }
+
+    /// The name of a session, its path relative to its directory without the
+    /// extension, prefixed by the label of the directory unless that is the
+    /// primary one.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    #[must_use]
+    pub fn name_of(&self, session: &Path) -> String {
+        let source = self.source(session);
+        let relative = source
+            .and_then(|dir| session.strip_prefix(&dir.path).ok())
+            .unwrap_or_else(|| Path::new(session.file_name().unwrap_or_default()));
+        let stem = relative
+            .with_extension("")
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join(&GROUP_SEPARATOR.to_string());
+        match source {
+            Some(dir) if Some(dir) != self.primary() => {
+                format!("{}{}{}", dir.label, LABEL_SEPARATOR, stem)
+            }
+            _ => stem,
+        }
+    }
+
+    /// The key the metadata and the open history of a session are kept
+    /// under. That is its name, unless the session is not kept in one of the
+    /// session directories, such as a project session, which is keyed by its
+    /// canonical path. Unlike its name that does not depend on the projects
+    /// discovered from the working directory.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    #[must_use]
+    pub fn key_of(&self, session: &Path) -> String {
+        match self.source(session) {
+            Some(dir) if !dir.project => self.name_of(session),
+            _ => fs::canonicalize(session)
+                .unwrap_or_else(|_| session.to_path_buf())
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    /// The names of several sessions, in the same order.
+    #[must_use]
+    pub fn names(&self, sessions: &[PathBuf]) -> Vec<String> {
+        sessions
+            .iter()
+            .map(|session| self.name_of(session))
+            .collect()
+    }
+
+    /// Where the session with a name is, or would be, kept. `label:name`
+    /// points into the directory with that label, other names into the
+    /// primary directory. A grouped name, `work/api`, points into the
+    /// sub-directory of its group.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionName if the name or one of its groups is
+    ///       invalid, or no directory has its label.
+    pub fn path_for(&self, name: &str) -> Result<PathBuf, VsmRuntimeFault> {
+        let name = name.strip_suffix(".vim").unwrap_or(name);
+        let (dir, stem) = match name.split_once(LABEL_SEPARATOR) {
+            Some((label, stem)) => (
+                self.dirs
+                    .iter()
+                    .find(|dir| dir.label == label)
+                    .ok_or_else(|| VsmRuntimeFault::SessionName {
+                        msg: format!("No session directory is labelled '{}'", label),
+                    })?,
+                stem,
+            ),
+            None => (
+                self.primary().ok_or_else(|| VsmRuntimeFault::SessionName {
+                    msg: "No session directory is configured".to_owned(),
+                })?,
+                name,
+            ),
+        };
+        let mut path = dir.path.clone();
+        let mut parts = stem.split(GROUP_SEPARATOR).peekable();
+        while let Some(part) = parts.next() {
+            validate_session_name(part)?;
+            if parts.peek().is_some() {
+                path.push(part);
+            } else {
+                path.push(format!("{}.vim", part));
+            }
+        }
+        Ok(path)
+    }
+
+    /// Collects the session files of every directory and their
+    /// sub-directories, up to the scan depth. The primary directory comes
+    /// first, each directory sorted by path. Missing directories are skipped,
+    /// and of a project only its `Session.vim` is read.
+    ///
+    /// # Errors
+    ///     * io::Error if an existing directory can not be read.
+    pub fn scan(&self) -> Result<Vec<PathBuf>, io::Error> {
+        let mut sessions: Vec<PathBuf> = vec![];
+        for dir in &self.dirs {
+            if !dir.path.is_dir() {
+                debug!(
+                    "Skipping missing session directory => {}",
+                    dir.path.display()
+                );
+                continue;
+            }
+            let mut found: Vec<PathBuf> = vec![];
+            if dir.project {
+                found.push(dir.path.join(PROJECT_SESSION_FILE));
+                found.retain(|path| path.is_file());
+            } else {
+                collect_sessions(&dir.path, self.depth, &mut found)?;
+            }
+            found.sort();
+            // A directory nested in another one was read with its parent
+            found.retain(|path| !sessions.contains(path));
+            sessions.extend(found);
+        }
+        Ok(sessions)
+    }
+
+    /// Collects the session files of every directory, see `scan`. The
+    /// primary directory is created if it does not exist yet.
+    ///
+    /// # Returns
+    ///     * None if no session file was found, this is how we can tell if we
+    ///       have session files to list, or remove or open.
+    ///
+    /// # Errors
+    ///     * io::Error if the primary directory can not be created, or an
+    ///       existing directory can not be read.
+    pub fn load(&self) -> Result<Option<Vec<PathBuf>>, io::Error> {
+        if let Some(primary) = self.primary().filter(|dir| !dir.path.is_dir()) {
+            fs::create_dir_all(&primary.path)?;
+        }
+        let sessions = self.scan()?;
+        if sessions.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(sessions))
+    }
+}
+
+/// Collects the session files in a directory, descending into the
+/// sub-directories that are not hidden while depth is left.
+fn collect_sessions(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if let Some(left) = depth.checked_sub(1).filter(|_| !hidden) {
+                collect_sessions(&path, left, found)?;
+            }
+        } else if path.is_file()
+            && path.extension().is_some_and(|ext| ext == "vim")
+            && !is_companion(&path)
+        {
+            // Only files with a vim extension can be opened as sessions,
+            // anything else the user keeps in there is dropped, and so are
+            // the extra files sourced after a session.
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{free_name, session_group, SessionDir, SessionDirs};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_free_name_skips_taken_names() {
+        let taken = ["api", "api-2"];
+        assert_eq!(free_name("api", |name| taken.contains(&name)), "api-3");
+    }
+
+    #[test]
+    fn test_from_list_labels_and_names() {
+        let dirs = SessionDirs::from_list("/home/al/sessions:team=/shared/vim:/srv/sessions")
+            .with_dirs([
+                SessionDir::new("work", Path::new("/shared/vim/")),
+                SessionDir::new("", Path::new("/data/team")),
+            ]);
+        let labels: Vec<&str> = dirs.dirs().iter().map(|dir| dir.label().as_str()).collect();
+        assert_eq!(labels, vec!["sessions", "team", "sessions-2", "team-2"]);
+
+        assert_eq!(dirs.name_of(Path::new("/home/al/sessions/api.vim")), "api");
+        assert_eq!(dirs.name_of(Path::new("/shared/vim/api.vim")), "team:api");
+        assert_eq!(dirs.key_of(Path::new("/shared/vim/api.vim")), "team:api");
+        assert_eq!(
+            dirs.key_of(Path::new("/elsewhere/Session.vim")),
+            "/elsewhere/Session.vim"
+        );
+        assert_eq!(
+            dirs.path_for("team:api").expect("label is known"),
+            PathBuf::from("/shared/vim/api.vim")
+        );
+        assert_eq!(
+            dirs.path_for("web.vim").expect("bare names are primary"),
+            PathBuf::from("/home/al/sessions/web.vim")
+        );
+        dirs.path_for("nope:api").expect_err("unknown label");
+    }
+
+    #[test]
+    fn test_from_list_keeps_equal_signs_in_paths() {
+        let dirs = SessionDirs::from_list("/srv/a=b:team=/shared/x=y:./c=d:solo=");
+        let paths: Vec<&Path> = dirs.dirs().iter().map(|dir| dir.path().as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("/srv/a=b"),
+                Path::new("/shared/x=y"),
+                Path::new("./c=d"),
+                Path::new("solo=")
+            ]
+        );
+        let labels: Vec<&str> = dirs.dirs().iter().map(|dir| dir.label().as_str()).collect();
+        assert_eq!(labels, vec!["a=b", "team", "c=d", "solo="]);
+    }
+
+    #[test]
+    fn test_scan_names_sessions_by_relative_path() {
+        let sandbox = test_dir("dirs");
+        let root = sandbox.path();
+        for dir in ["work/deep/deeper", ".git", "oss"] {
+            fs::create_dir_all(root.join(dir)).expect("Failed to create the sandbox");
+        }
+        for file in [
+            "zsh.vim",
+            "work/api.vim",
+            "work/apix.vim",
+            "work/deep/web.vim",
+            "work/deep/deeper/too-deep.vim",
+            ".git/hidden.vim",
+            "oss/notes.txt",
+        ] {
+            fs::write(root.join(file), "").expect("Failed to write a session");
+        }
+
+        let dirs = SessionDirs::from_list(&root.to_string_lossy()).with_depth(2);
+        let names = dirs.names(&dirs.scan().expect("sessions are scanned"));
+        assert_eq!(names, vec!["work/api", "work/deep/web", "zsh"]);
+        assert_eq!(session_group("work/deep/web"), Some("work/deep"));
+        assert_eq!(session_group("zsh"), None);
+        assert_eq!(
+            dirs.path_for("work/api").expect("groups are accepted"),
+            root.join("work/api.vim")
+        );
+        dirs.path_for("work/../api")
+            .expect_err("groups are validated");
+        dirs.path_for("work//api")
+            .expect_err("empty groups are rejected");
+    }
+}
@@ -0,0 +1,152 @@
+//! Validates a session against the filesystem, so rotten sessions can be
+//! found before vim greets the user with a wall of errors.
+
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use derive_getters::Getters;
+
+use super::parser::SessionFile;
+
+/// Something wrong with a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionProblem {
+    /// The session file can not be read, holds the reason
+    Unreadable(String),
+    /// The session refers to no files at all
+    Empty,
+    /// The directory the session changes to does not exist
+    MissingDirectory(String),
+    /// A file the session refers to does not exist
+    MissingFile(String),
+}
+
+impl Display for SessionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Unreadable(ref reason) => write!(f, "unreadable, {}", reason),
+            Self::Empty => write!(f, "empty session"),
+            Self::MissingDirectory(ref cwd) => write!(f, "missing directory {}", cwd),
+            Self::MissingFile(ref file) => write!(f, "missing file {}", file),
+        }
+    }
+}
+
+/// The result of checking a single session.
+#[derive(Debug, Clone, Getters)]
+pub struct SessionHealth {
+    /// File name without the `.vim` extension
+    name: String,
+    /// Path to the session file
+    path: PathBuf,
+    /// Everything that is wrong with the session, empty if it is healthy
+    problems: Vec<SessionProblem>,
+}
+
+impl SessionHealth {
+    /// Checks a session file. Never fails, a session that can not be read is
+    /// reported as a problem.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///     * home The home directory to expand ~ to.
+    #[must_use]
+    pub fn check(session: &Path, home: &str) -> Self {
+        let problems = match fs::read_to_string(session) {
+            Ok(contents) => Self::diagnose(&SessionFile::parse(&contents), home),
+            Err(e) => vec![SessionProblem::Unreadable(e.to_string())],
+        };
+        Self {
+            name: session
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: session.to_path_buf(),
+            problems,
+        }
+    }
+
+    /// Reports the session under another name, such as the one qualified by
+    /// the label of its directory.
+    #[must_use]
+    pub fn with_name(mut self, name: &str) -> Self {
+        name.clone_into(&mut self.name);
+        self
+    }
+
+    /// Returns true if nothing is wrong with the session.
+    #[must_use]
+    pub const fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Collects the problems of a parsed session.
+    fn diagnose(file: &SessionFile, home: &str) -> Vec<SessionProblem> {
+        let files = file.files();
+        if files.is_empty() && file.terminals().is_empty() {
+            return vec![SessionProblem::Empty];
+        }
+        let mut problems: Vec<SessionProblem> = vec![];
+        if let Some(cwd) = file.cwd().as_deref() {
+            if !file.resolve_path(cwd, home).is_dir() {
+                problems.push(SessionProblem::MissingDirectory(cwd.to_owned()));
+            }
+        }
+        for missing in files
+            .into_iter()
+            .filter(|path| !file.resolve_path(path, home).exists())
+        {
+            problems.push(SessionProblem::MissingFile(missing.to_owned()));
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{SessionHealth, SessionProblem};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_check_reports_problems() {
+        let sandbox = test_dir("health");
+        let root = sandbox.path();
+        fs::write(root.join("present.rs"), "").expect("Failed to write a buffer");
+        let home = root.to_string_lossy().to_string();
+
+        let healthy = root.join("healthy.vim");
+        fs::write(&healthy, "cd ~\nbadd +1 present.rs\n").expect("Failed to write a session");
+        assert!(SessionHealth::check(&healthy, &home).is_healthy());
+
+        let rotten = root.join("rotten.vim");
+        fs::write(&rotten, "cd ~/moved\nbadd +1 present.rs\nedit /gone.rs\n")
+            .expect("Failed to write a session");
+        assert_eq!(
+            SessionHealth::check(&rotten, &home).problems(),
+            &vec![
+                SessionProblem::MissingDirectory("~/moved".to_owned()),
+                SessionProblem::MissingFile("present.rs".to_owned()),
+                SessionProblem::MissingFile("/gone.rs".to_owned()),
+            ]
+        );
+
+        let empty = root.join("empty.vim");
+        fs::write(&empty, "").expect("Failed to write a session");
+        assert_eq!(
+            SessionHealth::check(&empty, &home).problems(),
+            &vec![SessionProblem::Empty]
+        );
+
+        assert!(matches!(
+            SessionHealth::check(&root.join("absent.vim"), &home)
+                .problems()
+                .first(),
+            Some(&SessionProblem::Unreadable(_))
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+//! Remembers when sessions were opened, so they can be listed most recently
+//! or most frequently used first. Kept in the vsm data directory, next to the
+//! session metadata.
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::store::{KeyedStore, Stored};
+use crate::error::VsmRuntimeFault;
+
+/// Name of the history file inside the vsm data directory.
+const HISTORY_FILE: &str = "history.toml";
+
+/// How often, and how recently a session was opened.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct OpenRecord {
+    /// Time of the last open, in seconds since the unix epoch
+    last_opened: u64,
+    /// How many times the session was opened
+    count: u64,
+    /// Exit code of the editor the last time, None if it was killed by a
+    /// signal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exit_status: Option<i32>,
+}
+
+impl Stored for OpenRecord {
+    const FILE: &'static str = HISTORY_FILE;
+
+    fn fault(msg: String) -> VsmRuntimeFault {
+        VsmRuntimeFault::History { msg }
+    }
+}
+
+/// The open history of all sessions, read from and written to a single toml
+/// file.
+pub type OpenHistory = KeyedStore<OpenRecord>;
+
+impl KeyedStore<OpenRecord> {
+    /// Records an open of a session.
+    ///
+    /// # Arguments
+    ///     * session Session name.
+    ///     * at Time of the open, in seconds since the unix epoch.
+    ///     * exit_status Exit code of the editor.
+    pub fn record(&mut self, session: &str, at: u64, exit_status: Option<i32>) {
+        let record = self.entry(session);
+        record.last_opened = at;
+        record.count = record.count.saturating_add(1);
+        record.exit_status = exit_status;
+    }
+
+    /// The session that was opened last, if any was.
+    #[cfg(any(test, feature = "cli"))]
+    #[must_use]
+    pub fn last(&self) -> Option<&str> {
+        self.sessions()
+            .iter()
+            .max_by_key(|&(_, record)| record.last_opened)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::OpenHistory;
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_history_records_opens() {
+        let sandbox = test_dir("history");
+        let path = sandbox.path().join("history.toml");
+        let mut history = OpenHistory::load(&path).expect("missing history is empty");
+        for _ in 0..5 {
+            history.record("api", 10, Some(0));
+        }
+        history.record("web", 20, Some(1));
+        history.save().expect("history is written");
+
+        let reloaded = OpenHistory::load(&path).expect("history is read");
+        assert_eq!(reloaded.last(), Some("web"));
+        assert_eq!(reloaded.get("api").map(|r| *r.count()), Some(5));
+        assert_eq!(reloaded.get("web").and_then(|r| *r.exit_status()), Some(1));
+    }
+}
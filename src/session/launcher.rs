@@ -0,0 +1,298 @@
+//! Opens session files in the users vim variant.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::debug;
+
+use super::dirs::SessionDirs;
+use super::history::OpenHistory;
+use super::portable::open_portable;
+use super::startup::{autosave_commands, editor_arguments, save_on_exit_commands};
+use crate::config::{AutosaveConfig, PortableConfig, TomlConfigFile, VimVariant, ENVIRONMENT};
+use crate::error::VsmRuntimeFault;
+use crate::utils::{CommandExecutor, FilesystemManager, Shell};
+
+/// Launches a vim variant with a session file, blocking until the editor exits.
+#[derive(Debug)]
+pub struct SessionLauncher {
+    /// The vim variant, and the flags it needs to source a session
+    variant: VimVariant,
+    /// Spawns the editor process
+    shell: Box<dyn Shell>,
+    /// Decides which sessions the editor rewrites
+    autosave: AutosaveConfig,
+    /// Decides which sessions are kept portable
+    portable: PortableConfig,
+    /// The session directories, which name the sessions
+    dirs: SessionDirs,
+    /// Directory holding the open history
+    data_dir: PathBuf,
+    /// Home directory `~` in portable sessions stands for
+    home: String,
+}
+
+impl SessionLauncher {
+    /// Builds a launcher for a specific vim variant, nothing is read from
+    /// the environment.
+    ///
+    /// # Arguments
+    ///     * variant The vim variant to open sessions with.
+    ///     * session_dir Absolute path to the session directory, or a colon
+    ///       separated list of them, which name the sessions.
+    ///     * data_dir Absolute path to the data directory, the open history
+    ///       is recorded there.
+    ///     * home The home directory ~ in portable sessions stands for.
+    #[must_use]
+    pub fn new(variant: VimVariant, session_dir: &str, data_dir: &str, home: &str) -> Self {
+        Self {
+            variant,
+            shell: Box::new(CommandExecutor::new()),
+            autosave: AutosaveConfig::default(),
+            portable: PortableConfig::default(),
+            dirs: SessionDirs::from_list(session_dir),
+            data_dir: PathBuf::from(data_dir),
+            home: home.to_owned(),
+        }
+    }
+
+    /// Spawns the editor through a custom shell.
+    ///
+    /// # Arguments
+    ///     * shell Spawns the editor process.
+    #[must_use]
+    pub fn with_shell(mut self, shell: Box<dyn Shell>) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    /// Makes the editor rewrite the sessions the settings apply to, see
+    /// `AutosaveConfig`.
+    ///
+    /// # Arguments
+    ///     * autosave The autosave settings.
+    #[must_use]
+    pub fn with_autosave(mut self, autosave: AutosaveConfig) -> Self {
+        self.autosave = autosave;
+        self
+    }
+
+    /// Makes the sessions the settings apply to portable again after the
+    /// editor saved them, see `PortableConfig`.
+    ///
+    /// # Arguments
+    ///     * portable The portable settings.
+    #[must_use]
+    pub fn with_portable(mut self, portable: PortableConfig) -> Self {
+        self.portable = portable;
+        self
+    }
+
+    /// Builds a launcher from the environment, the way the `vsm` binary
+    /// does: the vim variant the user picked with `vsm variant`, or the
+    /// default variant if vsm was never configured, the autosave and
+    /// portable settings and the session directories of the config.toml,
+    /// `$VIM_SESSIONS`, `$HOME`, `~/.local/share/vsm` and the project
+    /// sessions around the working directory.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::TomlConfigFileRead if the config.toml exists but
+    ///       can not be read.
+    pub fn from_env() -> Result<Self, VsmRuntimeFault> {
+        let fs = FilesystemManager::new(ENVIRONMENT.path().vsm_config_file());
+        let config = if fs.config_file_exists() {
+            fs.read_config()?
+        } else {
+            TomlConfigFile::default()
+        };
+        let home = ENVIRONMENT.var().home();
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let mut launcher = Self::new(
+            config.vim_variant().clone(),
+            ENVIRONMENT.var().vim_sessions(),
+            ENVIRONMENT.path().vsm_data_dir(),
+            home,
+        )
+        .with_autosave(config.autosave().clone())
+        .with_portable(config.portable().clone());
+        launcher.dirs = launcher.dirs.configure(&config, home, &cwd);
+        Ok(launcher)
+    }
+
+    /// The vim variant sessions are opened with.
+    #[must_use]
+    pub const fn variant(&self) -> &VimVariant {
+        &self.variant
+    }
+
+    /// Opens a session file and waits for the editor to exit, the way
+    /// `vsm open` does. If autosave applies to the session the editor
+    /// rewrites it, a portable session is opened with its paths expanded
+    /// for the home directory and made portable again if the editor saved
+    /// it, and the open is recorded in the history.
+    ///
+    /// # Arguments
+    ///     * session_file Path to the session file.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::CommandExecutor if the editor can not be spawned.
+    ///     * VsmRuntimeFault::Relocate if a portable session can not be
+    ///       rewritten.
+    ///     * VsmRuntimeFault::History if the history can not be updated.
+    pub fn launch(&self, session_file: &Path) -> Result<(), VsmRuntimeFault> {
+        let name = self.dirs.name_of(session_file);
+        let commands = if self.autosave.applies_to(&name) {
+            autosave_commands(
+                session_file,
+                *self.autosave.interval(),
+                *self.autosave.buf_enter(),
+            )
+        } else {
+            vec![]
+        };
+        let launch = || {
+            self.shell.open_editor_with_session(
+                self.variant.active_variant(),
+                self.variant.shell_command(),
+                &session_file.to_string_lossy(),
+                &commands,
+            )
+        };
+        let launched = if self.portable.applies_to(&name) {
+            let (launched, saved) = open_portable(session_file, &self.home, launch)?;
+            if saved {
+                debug!("Made {} portable", name);
+            }
+            launched
+        } else {
+            launch()
+        };
+        let exit_status = launched?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let mut history = OpenHistory::load_from_dir(&self.data_dir)?;
+        history.record(&self.dirs.key_of(session_file), now, exit_status);
+        history.save()
+    }
+
+    /// Starts the editor for a new session, which the editor writes to
+    /// `session_file` when it exits. See `vsm new`.
+    ///
+    /// # Arguments
+    ///     * session_file Path the session is written to.
+    ///     * cwd Working directory of the editor, the current one if None.
+    ///     * files Files to open.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::CommandExecutor if the editor can not be spawned.
+    pub fn create(
+        &self,
+        session_file: &Path,
+        cwd: Option<&Path>,
+        files: &[String],
+    ) -> Result<(), VsmRuntimeFault> {
+        let arguments = editor_arguments(
+            self.variant.shell_command(),
+            &save_on_exit_commands(session_file),
+            files,
+        );
+        self.shell
+            .launch_editor(self.variant.active_variant(), &arguments, cwd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::SessionLauncher;
+    use crate::config::TomlConfigFile;
+    use crate::error::VsmRuntimeFault;
+    use crate::session::history::OpenHistory;
+    use crate::testing::test_dir;
+    use crate::utils::Shell;
+
+    /// Records the commands it is given, and saves the session with the
+    /// local home directory spelled out, as vim does.
+    #[derive(Debug, Default)]
+    struct SavingShell {
+        commands: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Shell for SavingShell {
+        fn is_installed(&self, _program: &str) -> bool {
+            true
+        }
+
+        fn open_editor_with_session(
+            &self,
+            _vim_variant: &str,
+            _shell_command: &str,
+            session_file: &str,
+            commands: &[String],
+        ) -> Result<Option<i32>, VsmRuntimeFault> {
+            self.commands.borrow_mut().extend_from_slice(commands);
+            fs::write(
+                session_file,
+                "cd /home/al/code/api\nbadd +1 /home/al/code/api/main.go\n",
+            )
+            .expect("session is saved");
+            Ok(Some(0))
+        }
+
+        fn launch_editor(
+            &self,
+            _vim_variant: &str,
+            _arguments: &[String],
+            _cwd: Option<&Path>,
+        ) -> Result<(), VsmRuntimeFault> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_launch_honors_the_session_name_and_records_history() {
+        let sandbox = test_dir("launcher");
+        let root = sandbox.path();
+        let sessions = root.join("sessions");
+        fs::create_dir_all(sessions.join("work")).expect("group is created");
+        let session = sessions.join("work").join("api.vim");
+        fs::write(&session, "cd ~/code/api\n").expect("session is written");
+        let config: TomlConfigFile = toml::from_str(
+            "[vim_variant]\nactive_variant = \"vim\"\nshell_command = \"-S\"\n\
+             [autosave]\nsessions = [\"work/api\"]\n\
+             [portable]\nsessions = [\"work/api\"]\n",
+        )
+        .expect("config is parsed");
+
+        let commands: Rc<RefCell<Vec<String>>> = Rc::default();
+        let shell = SavingShell {
+            commands: Rc::clone(&commands),
+        };
+        SessionLauncher::new(
+            config.vim_variant().clone(),
+            &sessions.to_string_lossy(),
+            &root.join("data").to_string_lossy(),
+            "/home/al",
+        )
+        .with_shell(Box::new(shell))
+        .with_autosave(config.autosave().clone())
+        .with_portable(config.portable().clone())
+        .launch(&session)
+        .expect("session is launched");
+
+        assert!(!commands.borrow().is_empty());
+        assert_eq!(
+            fs::read_to_string(&session).expect("session is read"),
+            "cd ~/code/api\nbadd +1 ~/code/api/main.go\n"
+        );
+        let history = OpenHistory::load_from_dir(&root.join("data")).expect("history is read");
+        assert_eq!(history.last(), Some("work/api"));
+    }
+}
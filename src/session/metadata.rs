@@ -0,0 +1,169 @@
+//! A sidecar store for what a session file can not hold itself, such as what
+//! the session is for. Kept in the vsm data directory, keyed by session name.
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::store::{KeyedStore, Stored};
+use crate::error::VsmRuntimeFault;
+
+/// Name of the metadata file inside the vsm data directory.
+const METADATA_FILE: &str = "metadata.toml";
+
+/// Everything vsm knows about a session, besides its file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct SessionMetadata {
+    /// A one line summary of what the session is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Free-form notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    /// Tags, without a leading `#`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Pinned sessions are offered first, and protected from removal
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    /// User that created the session through vsm
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_by: Option<String>,
+    /// Vim variant the session was created with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_with: Option<String>,
+    /// Creation time, in seconds since the unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<u64>,
+}
+
+impl SessionMetadata {
+    /// Returns true if nothing is recorded, such entries are not stored.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Sets the description, an empty one clears it.
+    #[cfg(feature = "cli")]
+    pub fn set_description(&mut self, description: &str) {
+        self.description = non_empty(description);
+    }
+
+    /// Sets the note, an empty one clears it.
+    #[cfg(feature = "cli")]
+    pub fn set_note(&mut self, note: &str) {
+        self.note = non_empty(note);
+    }
+
+    /// Adds a tag, unless the session already has it. A leading `#` is
+    /// dropped.
+    ///
+    /// # Returns
+    ///     * true if the tag was added.
+    #[cfg(feature = "cli")]
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().trim_start_matches('#');
+        if tag.is_empty() || self.tags.iter().any(|t| t == tag) {
+            return false;
+        }
+        self.tags.push(tag.to_owned());
+        true
+    }
+
+    /// Removes a tag. A leading `#` is ignored.
+    ///
+    /// # Returns
+    ///     * true if the session had the tag.
+    #[cfg(feature = "cli")]
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().trim_start_matches('#');
+        let before = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != before
+    }
+
+    /// Pins or unpins the session.
+    ///
+    /// # Returns
+    ///     * true if the session was not pinned, or unpinned, already.
+    #[cfg(feature = "cli")]
+    pub const fn set_pinned(&mut self, pinned: bool) -> bool {
+        let changed = self.pinned != pinned;
+        self.pinned = pinned;
+        changed
+    }
+
+    /// Records who created the session, and with which vim variant.
+    ///
+    /// # Arguments
+    ///     * user Name of the user.
+    ///     * variant The vim variant the session was created with.
+    ///     * at Creation time, in seconds since the unix epoch.
+    #[cfg(feature = "cli")]
+    pub fn set_created(&mut self, user: &str, variant: &str, at: u64) {
+        self.created_by = Some(user.to_owned());
+        self.created_with = Some(variant.to_owned());
+        self.created_at = Some(at);
+    }
+}
+
+/// Trims a value, treating an empty one as unset.
+#[cfg(feature = "cli")]
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_owned())
+}
+
+impl Stored for SessionMetadata {
+    const FILE: &'static str = METADATA_FILE;
+
+    fn fault(msg: String) -> VsmRuntimeFault {
+        VsmRuntimeFault::Metadata { msg }
+    }
+
+    fn is_blank(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+/// The metadata of all sessions, read from and written to a single toml file.
+pub type MetadataStore = KeyedStore<SessionMetadata>;
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use super::MetadataStore;
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_store_round_trip_and_rename() {
+        let sandbox = test_dir("metadata");
+        let root = sandbox.path();
+        let path = root.join("data").join("metadata.toml");
+        let mut store = MetadataStore::load(&path).expect("missing store is empty");
+        assert!(store.sessions().is_empty());
+
+        let api = store.entry("api");
+        api.set_description("  REST gateway ");
+        assert!(api.add_tag("#work"));
+        assert!(!api.add_tag("work"));
+        assert!(api.add_tag("go"));
+        assert!(api.remove_tag("go"));
+        store.entry("empty").set_note(" ");
+        assert!(store.entry("pinned").set_pinned(true));
+        assert!(!store.entry("pinned").set_pinned(true));
+        assert!(store.entry("pinned").set_pinned(false));
+        store.rename("api", "gateway");
+        store.save().expect("store is written");
+
+        let reloaded = MetadataStore::load(&path).expect("store is read");
+        assert_eq!(reloaded.sessions().len(), 1);
+        let gateway = reloaded
+            .get("gateway")
+            .expect("metadata followed the rename");
+        assert_eq!(gateway.description().as_deref(), Some("REST gateway"));
+        assert_eq!(gateway.tags(), &vec!["work".to_owned()]);
+    }
+}
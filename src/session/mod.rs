@@ -0,0 +1,64 @@
+//! Session discovery and launching. The catalog and the launcher are the
+//! library facing side of vsm, the rest only backs the command line interface.
+
+#[cfg(feature = "cli")]
+mod bundle;
+mod catalog;
+mod companion;
+#[cfg(feature = "cli")]
+mod details;
+mod dirs;
+mod health;
+mod history;
+mod launcher;
+mod metadata;
+mod parser;
+mod portable;
+mod project;
+#[cfg(feature = "cli")]
+mod prune;
+mod record;
+mod relocate;
+mod rename;
+#[cfg(feature = "cli")]
+mod sort;
+mod startup;
+mod store;
+mod trash;
+#[cfg(feature = "cli")]
+mod workdir;
+
+#[cfg(feature = "cli")]
+pub use bundle::{BundledSession, ClashPolicy, SessionBundle};
+pub use catalog::SessionCatalog;
+#[cfg(feature = "cli")]
+pub use companion::{companion_of, companion_path};
+#[cfg(feature = "cli")]
+pub use details::SessionDetails;
+#[cfg(feature = "cli")]
+pub use dirs::{free_name, session_group, SessionDir, SessionDirs, LABEL_SEPARATOR};
+pub use health::{SessionHealth, SessionProblem};
+#[cfg(feature = "cli")]
+pub use history::OpenHistory;
+pub use launcher::SessionLauncher;
+#[cfg(feature = "cli")]
+pub use metadata::{MetadataStore, SessionMetadata};
+#[cfg(feature = "cli")]
+pub use parser::{Split, TabPage};
+#[cfg(feature = "cli")]
+pub use portable::{open_portable, to_portable, unportable_paths};
+#[cfg(feature = "cli")]
+pub use prune::{parse_age, AgeBy, PrunePolicy};
+pub use record::SessionRecord;
+#[cfg(feature = "cli")]
+pub use relocate::{backup_session, LineChange, Relocation};
+#[cfg(feature = "cli")]
+pub use rename::move_session;
+#[cfg(feature = "cli")]
+pub use sort::SessionSort;
+#[cfg(feature = "cli")]
+pub use startup::{autosave_commands, editor_arguments, save_on_exit_commands};
+#[cfg(feature = "cli")]
+pub use trash::Trash;
+#[cfg(feature = "cli")]
+pub use workdir::rank_by_directory;
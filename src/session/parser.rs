@@ -0,0 +1,732 @@
+//! Parses the vim script written by `:mksession` into a typed model. Output of
+//! both Vim and Neovim is understood, lines that are not are reported on the
+//! model instead of failing the parse.
+
+use std::fmt::{self, Display};
+#[cfg(feature = "cli")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+#[cfg(feature = "cli")]
+use crate::error::VsmRuntimeFault;
+
+/// Command modifiers mksession puts in front of the commands it writes.
+const MODIFIERS: [&str; 9] = [
+    "silent!",
+    "silent",
+    "sil!",
+    "sil",
+    "keepjumps",
+    "keepalt",
+    "noautocmd",
+    "vert",
+    "vertical",
+];
+
+/// Options mksession sets to restore a layout, and resets afterwards. They say
+/// nothing about the users setup so they are left out of the option lines.
+const MACHINERY_OPTIONS: [&str; 14] = [
+    "cp",
+    "nocp",
+    "stal",
+    "showtabline",
+    "shortmess",
+    "shm",
+    "splitbelow",
+    "splitright",
+    "nosplitbelow",
+    "nosplitright",
+    "winminheight",
+    "winheight",
+    "winminwidth",
+    "winwidth",
+];
+
+/// Commands that are part of every session, but carry nothing the model holds.
+const IGNORED_COMMANDS: [&str; 29] = [
+    "unlet",
+    "if",
+    "else",
+    "elseif",
+    "endif",
+    "exe",
+    "execute",
+    "normal",
+    "norm",
+    "doautoall",
+    "nohlsearch",
+    "noh",
+    "resize",
+    "balt",
+    "enew",
+    "file",
+    "only",
+    "tabonly",
+    "argdel",
+    "source",
+    "so",
+    "call",
+    "fold",
+    "hi",
+    "highlight",
+    "filetype",
+    "syntax",
+    "colorscheme",
+    "lua",
+];
+
+/// Mapping and abbreviation commands, written when `sessionoptions` holds
+/// `options`.
+const MAPPING_COMMANDS: [&str; 22] = [
+    "map", "noremap", "nmap", "nnoremap", "vmap", "vnoremap", "xmap", "xnoremap", "smap",
+    "snoremap", "omap", "onoremap", "imap", "inoremap", "cmap", "cnoremap", "lmap", "lnoremap",
+    "tmap", "tnoremap", "abbr", "iabbrev",
+];
+
+/// The editor that most likely wrote a session file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Editor {
+    /// Vim, or gvim
+    Vim,
+    /// Neovim, or a Neovim GUI such as neovide
+    Neovim,
+    /// Not enough evidence either way
+    #[default]
+    Unknown,
+}
+
+impl Display for Editor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Vim => write!(f, "vim"),
+            Self::Neovim => write!(f, "neovim"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// How a window was split off from the rest of its tab page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Split {
+    /// `split`, the windows are stacked
+    Horizontal,
+    /// `vsplit`, the windows are side by side
+    Vertical,
+}
+
+/// A buffer from the buffer list of the session (`badd`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Getters)]
+pub struct Buffer {
+    /// Path as written in the session, `~` is not expanded
+    path: String,
+    /// Line the cursor was on, if recorded
+    line: Option<u32>,
+    /// True for terminal buffers, they have no file on disk
+    terminal: bool,
+}
+
+/// A window inside a tab page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Getters)]
+pub struct Window {
+    /// The buffer shown in the window, if one was named
+    buffer: Option<String>,
+    /// How the window was split off, None for the first window of a tab page
+    split: Option<Split>,
+    /// Window local working directory (`lcd`)
+    cwd: Option<String>,
+}
+
+/// A tab page, with its windows in the order `wincmd w` visits them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Getters)]
+pub struct TabPage {
+    /// Tab local working directory (`tcd`)
+    cwd: Option<String>,
+    /// Windows of the tab page, there is always at least one
+    windows: Vec<Window>,
+}
+
+impl Default for TabPage {
+    fn default() -> Self {
+        Self {
+            cwd: None,
+            windows: vec![Window::default()],
+        }
+    }
+}
+
+/// Whether an option line sets global or local values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionScope {
+    /// `set` or `setglobal`
+    Global,
+    /// `setlocal`
+    Local,
+}
+
+/// An option line of the session, see `sessionoptions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Getters)]
+pub struct OptionLine {
+    /// Scope of the values that are set
+    scope: OptionScope,
+    /// The line as written
+    line: String,
+}
+
+/// A line the parser did not understand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Getters)]
+pub struct UnrecognizedLine {
+    /// Line number, starting at 1
+    number: usize,
+    /// The line as written
+    line: String,
+}
+
+/// The parsed contents of a session file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Getters)]
+pub struct SessionFile {
+    /// The editor that most likely wrote the session
+    editor: Editor,
+    /// Global working directory (`cd`), as written
+    cwd: Option<String>,
+    /// Expression `v:this_session` is assigned, tells the editor where a later
+    /// `:mksession!` writes to
+    this_session: Option<String>,
+    /// The buffer list
+    buffers: Vec<Buffer>,
+    /// The global argument list
+    args: Vec<String>,
+    /// Tab pages and their windows, there is always at least one
+    tabs: Vec<TabPage>,
+    /// The tab page that was current, starting at 1
+    active_tab: Option<usize>,
+    /// Option lines, without the ones mksession uses to restore the layout
+    options: Vec<OptionLine>,
+    /// Terminal buffers and windows
+    terminals: Vec<String>,
+    /// Lines that were not understood
+    unrecognized: Vec<UnrecognizedLine>,
+}
+
+impl SessionFile {
+    /// Parses the contents of a session file. Never fails, anything that is
+    /// not understood ends up in `unrecognized`.
+    ///
+    /// # Arguments
+    ///     * contents The vim script written by `:mksession`.
+    #[must_use]
+    pub fn parse(contents: &str) -> Self {
+        let mut parser = Parser::default();
+        for (idx, line) in contents.lines().enumerate() {
+            parser.line(idx.saturating_add(1), line);
+        }
+        parser.finish(contents)
+    }
+
+    /// Reads and parses a session file.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SessionFileRead if the file can not be read.
+    #[cfg(feature = "cli")]
+    pub fn from_path(session: &Path) -> Result<Self, VsmRuntimeFault> {
+        match fs::read_to_string(session) {
+            Ok(contents) => Ok(Self::parse(&contents)),
+            Err(e) => Err(VsmRuntimeFault::SessionFileRead {
+                msg: format!("{} => {}", session.display(), e),
+            }),
+        }
+    }
+
+    /// Resolves a path as written in the session to the file it names. `~` and
+    /// `$HOME` are expanded to `home`, relative paths are taken from the
+    /// global working directory of the session.
+    ///
+    /// # Arguments
+    ///     * path A path from the session, such as a buffer name.
+    ///     * home The home directory to expand `~` to.
+    #[must_use]
+    pub fn resolve_path(&self, path: &str, home: &str) -> PathBuf {
+        let expanded = expand_home(path, home);
+        match self.cwd.as_deref() {
+            Some(cwd) if expanded.is_relative() => expand_home(cwd, home).join(expanded),
+            _ => expanded,
+        }
+    }
+
+    /// Every distinct file the session refers to, the buffer list first and
+    /// then the files shown in windows. Terminals are left out.
+    #[must_use]
+    pub fn files(&self) -> Vec<&str> {
+        let mut files: Vec<&str> = vec![];
+        let buffers = self
+            .buffers
+            .iter()
+            .filter(|buffer| !buffer.terminal)
+            .map(|buffer| buffer.path.as_str());
+        let windows = self
+            .tabs
+            .iter()
+            .flat_map(|tab| tab.windows.iter())
+            .filter_map(|window| window.buffer.as_deref())
+            .filter(|buffer| !is_terminal(buffer));
+        for file in buffers.chain(windows) {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        files
+    }
+}
+
+/// Walks a session line by line, tracking the tab page and window the
+/// session is positioned on.
+#[derive(Debug, Default)]
+struct Parser {
+    /// The model being built
+    session: SessionFile,
+    /// Index of the current tab page
+    tab: usize,
+    /// Index of the current window in the current tab page
+    window: usize,
+    /// True while `arglocal` is in effect, window local arguments are skipped
+    local_args: bool,
+}
+
+impl Parser {
+    /// Processes a single line.
+    fn line(&mut self, number: usize, raw: &str) {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('"') {
+            return;
+        }
+        // `if bufexists("f") | buffer f | else | edit f | endif`
+        if line.starts_with("if bufexists(") {
+            if let Some(target) = line
+                .split(" | ")
+                .find_map(|segment| segment.trim().strip_prefix("buffer "))
+            {
+                self.show(first_file(target));
+            }
+            return;
+        }
+
+        let (vertical, command, rest) = split_command(line);
+        if !self.command(command, rest, vertical, line) {
+            self.session.unrecognized.push(UnrecognizedLine {
+                number,
+                line: line.to_owned(),
+            });
+        }
+    }
+
+    /// Applies a single ex command to the model.
+    ///
+    /// # Returns
+    ///     * false if the command is not understood.
+    fn command(&mut self, command: &str, rest: &str, vertical: bool, line: &str) -> bool {
+        match command {
+            // A bare line number, restoring the cursor
+            "" if rest.is_empty() => {}
+            "let" => {
+                if let Some((_, value)) = rest
+                    .strip_prefix("v:this_session")
+                    .and_then(|assignment| assignment.split_once('='))
+                {
+                    self.session.this_session = Some(value.trim().to_owned());
+                }
+            }
+            "cd" | "chdir" => {
+                if self.session.cwd.is_none() {
+                    self.session.cwd = first_file(rest);
+                }
+            }
+            "lcd" | "lchdir" => {
+                if let Some(window) = self.current_window() {
+                    window.cwd = first_file(rest);
+                }
+            }
+            "tcd" | "tchdir" => {
+                if let Some(tab) = self.session.tabs.get_mut(self.tab) {
+                    tab.cwd = first_file(rest);
+                }
+            }
+            "badd" => self.add_buffer(rest),
+            "argadd" => {
+                if !self.local_args {
+                    self.session.args.extend(split_words(rest));
+                }
+            }
+            "argglobal" => self.local_args = false,
+            "arglocal" => self.local_args = true,
+            "edit" | "e" | "buffer" | "b" => self.show(first_file(rest)),
+            "tabnew" | "tabedit" | "tabe" => {
+                self.session.tabs.push(TabPage {
+                    cwd: None,
+                    windows: vec![Window {
+                        buffer: first_file(rest),
+                        ..Window::default()
+                    }],
+                });
+                self.tab = self.session.tabs.len().saturating_sub(1);
+                self.window = 0;
+            }
+            "tabrewind" | "tabr" | "tabfirst" => {
+                self.tab = 0;
+                self.window = 0;
+            }
+            "tabnext" | "tabn" => match rest.trim().parse::<usize>() {
+                // A numbered tabnext only appears at the end, selecting the current tab
+                Ok(active) => self.session.active_tab = Some(active),
+                Err(_) => {
+                    self.tab = self.tab.saturating_add(1);
+                    self.window = 0;
+                    while self.session.tabs.len() <= self.tab {
+                        self.session.tabs.push(TabPage::default());
+                    }
+                }
+            },
+            "split" | "sp" | "new" | "vsplit" | "vs" | "vnew" => {
+                let split = if vertical || command.starts_with('v') {
+                    Split::Vertical
+                } else {
+                    Split::Horizontal
+                };
+                if let Some(tab) = self.session.tabs.get_mut(self.tab) {
+                    tab.windows.push(Window {
+                        buffer: first_file(rest),
+                        split: Some(split),
+                        cwd: None,
+                    });
+                }
+            }
+            "wincmd" => self.move_window(rest.trim()),
+            "set" | "se" | "setglobal" | "setg" => self.add_option(OptionScope::Global, rest, line),
+            "setlocal" | "setl" => self.add_option(OptionScope::Local, rest, line),
+            "terminal" | "term" => {
+                let program = split_words(rest)
+                    .into_iter()
+                    .filter(|word| !word.starts_with("++"))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let name = format!("!{}", program);
+                self.show(Some(name.clone()));
+                self.session.terminals.push(name);
+            }
+            _ => {
+                return IGNORED_COMMANDS.contains(&command) || MAPPING_COMMANDS.contains(&command);
+            }
+        }
+        true
+    }
+
+    /// Handles `badd +<line> <file>`.
+    fn add_buffer(&mut self, rest: &str) {
+        let words = split_words(rest);
+        let line = words
+            .iter()
+            .find_map(|word| word.strip_prefix('+'))
+            .and_then(|number| number.parse::<u32>().ok());
+        if let Some(path) = words.into_iter().find(|word| !word.starts_with('+')) {
+            let terminal = is_terminal(&path);
+            if terminal {
+                self.session.terminals.push(path.clone());
+            }
+            self.session.buffers.push(Buffer {
+                path,
+                line,
+                terminal,
+            });
+        }
+    }
+
+    /// Records an option line, unless it only touches mksession machinery.
+    fn add_option(&mut self, scope: OptionScope, rest: &str, line: &str) {
+        let machinery = rest.split_whitespace().all(|assignment| {
+            let name = assignment
+                .split(['=', '^', '+', '-'])
+                .next()
+                .unwrap_or_default();
+            MACHINERY_OPTIONS.contains(&name)
+        });
+        if !machinery {
+            self.session.options.push(OptionLine {
+                scope,
+                line: line.to_owned(),
+            });
+        }
+    }
+
+    /// Shows a buffer in the current window.
+    fn show(&mut self, buffer: Option<String>) {
+        if buffer.is_none() {
+            return;
+        }
+        if let Some(window) = self.current_window() {
+            window.buffer = buffer;
+        }
+    }
+
+    /// Follows `wincmd` movements that matter for the window order.
+    fn move_window(&mut self, direction: &str) {
+        let last = self
+            .session
+            .tabs
+            .get(self.tab)
+            .map_or(0, |tab| tab.windows.len().saturating_sub(1));
+        match direction {
+            "t" => self.window = 0,
+            "w" => self.window = self.window.saturating_add(1).min(last),
+            "b" => self.window = last,
+            _ => {}
+        }
+    }
+
+    /// The window the session is positioned on.
+    fn current_window(&mut self) -> Option<&mut Window> {
+        self.session
+            .tabs
+            .get_mut(self.tab)
+            .and_then(|tab| tab.windows.get_mut(self.window))
+    }
+
+    /// Finishes the model, guessing which editor wrote the session.
+    fn finish(mut self, contents: &str) -> SessionFile {
+        self.session.editor = if self
+            .session
+            .buffers
+            .iter()
+            .any(|buffer| buffer.path.starts_with("term://"))
+        {
+            Editor::Neovim
+        } else if contents.contains("if &cp | set nocp | endif") {
+            // Neovim has no 'compatible' option, so it never writes this
+            Editor::Vim
+        } else if contents.contains("let SessionLoad = 1") {
+            Editor::Neovim
+        } else {
+            Editor::Unknown
+        };
+        self.session
+    }
+}
+
+impl Default for SessionFile {
+    fn default() -> Self {
+        Self {
+            editor: Editor::default(),
+            cwd: None,
+            this_session: None,
+            buffers: vec![],
+            args: vec![],
+            tabs: vec![TabPage::default()],
+            active_tab: None,
+            options: vec![],
+            terminals: vec![],
+            unrecognized: vec![],
+        }
+    }
+}
+
+/// Returns true if a buffer name refers to a terminal rather than a file.
+fn is_terminal(buffer: &str) -> bool {
+    buffer.starts_with("term://") || buffer.starts_with('!')
+}
+
+/// Splits a line into the command and its arguments, after stripping the
+/// modifiers and range in front of the command.
+///
+/// # Returns
+///     * (vertical, command, rest) vertical is true if the `vert` modifier was
+///       given.
+fn split_command(line: &str) -> (bool, &str, &str) {
+    let mut vertical = false;
+    let mut remaining = line;
+    loop {
+        let (word, rest) = remaining
+            .split_once(char::is_whitespace)
+            .unwrap_or((remaining, ""));
+        if !MODIFIERS.contains(&word) {
+            break;
+        }
+        vertical |= word.starts_with("vert");
+        remaining = rest.trim_start();
+    }
+    let remaining =
+        remaining.trim_start_matches(|c: char| c.is_ascii_digit() || "$%.,".contains(c));
+    let end = remaining
+        .char_indices()
+        .find(|&(_, c)| !c.is_ascii_alphabetic())
+        .map_or(remaining.len(), |(idx, _)| idx);
+    let (command, rest) = remaining.split_at(end);
+    (vertical, command, rest.trim_start_matches('!').trim())
+}
+
+/// Splits the arguments of a command into words, honoring backslash escapes
+/// the way `fnameescape()` writes them.
+fn split_words(rest: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Expands a leading `~` or `$HOME` in a path.
+fn expand_home(path: &str, home: &str) -> PathBuf {
+    if path == "~" || path == "$HOME" {
+        return PathBuf::from(home);
+    }
+    path.strip_prefix("~/")
+        .or_else(|| path.strip_prefix("$HOME/"))
+        .map_or_else(
+            || PathBuf::from(path),
+            |relative| Path::new(home).join(relative),
+        )
+}
+
+/// The file argument of a command, skipping `+<line>` and `++opt` arguments.
+fn first_file(rest: &str) -> Option<String> {
+    split_words(rest)
+        .into_iter()
+        .find(|word| !word.starts_with('+'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Editor, SessionFile, Split};
+    use crate::testing::{NEOVIM_SESSION, VIM_SESSION};
+
+    #[test]
+    fn test_parse_vim_session() {
+        let session = SessionFile::parse(VIM_SESSION);
+        assert_eq!(*session.editor(), Editor::Vim);
+        assert_eq!(session.cwd().as_deref(), Some("~/code/vsm"));
+        assert_eq!(
+            session.this_session().as_deref(),
+            Some("expand(\"<sfile>:p\")")
+        );
+        assert_eq!(session.args(), &vec!["src/main.rs".to_owned()]);
+        assert_eq!(session.buffers().len(), 2);
+        assert_eq!(
+            session
+                .buffers()
+                .get(1)
+                .map(|b| (b.path().as_str(), *b.line())),
+            Some(("src/my app.rs", Some(12)))
+        );
+        assert_eq!(session.tabs().len(), 2);
+        assert_eq!(
+            session
+                .tabs()
+                .iter()
+                .map(|tab| tab.windows().len())
+                .sum::<usize>(),
+            3
+        );
+        assert_eq!(*session.active_tab(), Some(1));
+
+        let first_tab = session.tabs().first().expect("first tab");
+        let buffers: Vec<Option<&str>> = first_tab
+            .windows()
+            .iter()
+            .map(|w| w.buffer().as_deref())
+            .collect();
+        assert_eq!(buffers, vec![Some("src/main.rs"), Some("src/my app.rs")]);
+        assert_eq!(
+            first_tab.windows().get(1).and_then(|w| *w.split()),
+            Some(Split::Vertical)
+        );
+        assert_eq!(
+            first_tab.windows().get(1).and_then(|w| w.cwd().as_deref()),
+            Some("~/code/vsm/src")
+        );
+
+        let options: Vec<&str> = session
+            .options()
+            .iter()
+            .map(|o| o.line().as_str())
+            .collect();
+        assert_eq!(options, vec!["setlocal fdm=manual", "set hlsearch"]);
+        assert_eq!(session.unrecognized(), &vec![]);
+    }
+
+    #[test]
+    fn test_parse_neovim_session_reports_unknown_lines() {
+        let session = SessionFile::parse(NEOVIM_SESSION);
+        assert_eq!(*session.editor(), Editor::Neovim);
+        assert_eq!(
+            session.terminals(),
+            &vec!["term://~/code/api//4242:/bin/zsh".to_owned()]
+        );
+        assert_eq!(session.files(), vec!["main.go"]);
+        assert_eq!(session.unrecognized().len(), 1);
+        assert_eq!(session.unrecognized().first().map(|u| *u.number()), Some(9));
+    }
+
+    #[test]
+    fn test_parse_old_vim_tabedit_session() {
+        let session = SessionFile::parse(
+            "if &cp | set nocp | endif\ncd /srv\nedit a.txt\ntabedit b.txt\nsplit c.txt\ntabnext 2\n",
+        );
+        assert_eq!(session.tabs().len(), 2);
+        assert_eq!(session.files(), vec!["a.txt", "b.txt", "c.txt"]);
+        assert_eq!(
+            session
+                .tabs()
+                .get(1)
+                .and_then(|t| t.windows().get(1))
+                .and_then(|w| *w.split()),
+            Some(Split::Horizontal)
+        );
+    }
+
+    #[test]
+    fn test_resolve_path_expands_home_and_cwd() {
+        let session = SessionFile::parse("cd ~/code\n");
+        assert_eq!(
+            session.resolve_path("src/main.rs", "/home/me"),
+            PathBuf::from("/home/me/code/src/main.rs")
+        );
+        assert_eq!(
+            session.resolve_path("$HOME/notes.md", "/home/me"),
+            PathBuf::from("/home/me/notes.md")
+        );
+        assert_eq!(
+            session.resolve_path("/etc/hosts", "/home/me"),
+            PathBuf::from("/etc/hosts")
+        );
+    }
+
+    #[test]
+    fn test_parse_garbage_does_not_fail() {
+        let session = SessionFile::parse("hello world\n\0\u{fffd}\n");
+        assert_eq!(session.tabs().len(), 1);
+        assert_eq!(session.unrecognized().len(), 2);
+    }
+}
@@ -0,0 +1,155 @@
+//! Keeps sessions portable between machines whose home directories differ,
+//! such as when `$VIM_SESSIONS` is synced with the dotfiles. A portable
+//! session spells the paths under the home directory with `~`. Vim does not
+//! expand a `~` inside the quoted `fnamemodify()` calls of a session, so the
+//! paths are expanded for the local home directory while the editor runs.
+
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "cli")]
+use derive_getters::Getters;
+
+use super::relocate::Relocation;
+#[cfg(feature = "cli")]
+use super::relocate::{contract_home, fname_escape, path_argument};
+use crate::error::VsmRuntimeFault;
+
+/// An absolute path of a session outside of the home directory, which stays
+/// as it is when the session is made portable.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct UnportablePath {
+    /// Line number, starting at 1
+    number: usize,
+    /// The path as written in the session
+    path: String,
+}
+
+/// Spells the paths under the home directory with `~`.
+///
+/// # Arguments
+///     * home The home directory of this machine.
+#[must_use]
+pub fn to_portable(home: &str) -> Relocation {
+    Relocation::from_prefixes(vec![(home.to_owned(), "~".to_owned())])
+}
+
+/// Spells the `~` paths with the home directory, the way the session was
+/// written on this machine.
+///
+/// # Arguments
+///     * home The home directory of this machine.
+#[must_use]
+fn from_portable(home: &str) -> Relocation {
+    Relocation::from_prefixes(vec![("~".to_owned(), home.to_owned())])
+}
+
+/// Runs the editor on a portable session with its paths expanded for the
+/// home directory of this machine. If the editor saved the session it is
+/// made portable again, otherwise the file is put back as it was.
+///
+/// # Arguments
+///     * session Path to the session file.
+///     * home The home directory of this machine.
+///     * open Runs the editor.
+///
+/// # Returns
+///     * What `open` returned, and true if the editor saved the session.
+///
+/// # Errors
+///     * VsmRuntimeFault::Relocate if the session can not be read or
+///       rewritten.
+pub fn open_portable<T>(
+    session: &Path,
+    home: &str,
+    open: impl FnOnce() -> T,
+) -> Result<(T, bool), VsmRuntimeFault> {
+    let fault = |e: std::io::Error| VsmRuntimeFault::Relocate {
+        msg: format!("{} => {}", session.display(), e),
+    };
+    let portable = fs::read_to_string(session).map_err(fault)?;
+    let local = from_portable(home).rewrite(&portable);
+    if local != portable {
+        fs::write(session, &local).map_err(fault)?;
+    }
+    let opened = open();
+    // Whatever the editor saved is made portable again, even if it failed
+    let saved = fs::read_to_string(session).ok().as_ref() != Some(&local);
+    if saved {
+        to_portable(home).apply_to(session)?;
+    } else if local != portable {
+        fs::write(session, &portable).map_err(fault)?;
+    }
+    Ok((opened, saved))
+}
+
+/// The absolute paths of a session that are not under the home directory, so
+/// they break on a machine laid out differently.
+///
+/// # Arguments
+///     * contents The vim script written by `:mksession`.
+///     * home The home directory of this machine.
+#[cfg(feature = "cli")]
+#[must_use]
+pub fn unportable_paths(contents: &str, home: &str) -> Vec<UnportablePath> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let path = path_argument(line)?;
+            (path.starts_with('/') && contract_home(path, &fname_escape(home)).is_none()).then(
+                || UnportablePath {
+                    number: idx.saturating_add(1),
+                    path: path.to_owned(),
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{from_portable, to_portable, unportable_paths};
+
+    #[test]
+    fn test_portable_spelling_and_unportable_paths() {
+        let session = "cd /home/alice/code/x\n\
+                       badd +3 /home/alice/code/x/main.rs\n\
+                       badd +1 /srv/shared/notes.md\n\
+                       edit /home/alicia/todo.md\n\
+                       let g:root = '/home/alice'\n\
+                       balt /home/alice/code/x/lib.rs\n\
+                       if bufexists(fnamemodify(\"/srv/x.md\", \":p\")) | buffer /srv/x.md | else | edit /srv/x.md | endif\n";
+        let portable = to_portable("/home/alice").rewrite(session);
+        assert_eq!(
+            portable,
+            "cd ~/code/x\n\
+             badd +3 ~/code/x/main.rs\n\
+             badd +1 /srv/shared/notes.md\n\
+             edit /home/alicia/todo.md\n\
+             let g:root = '/home/alice'\n\
+             balt ~/code/x/lib.rs\n\
+             if bufexists(fnamemodify(\"/srv/x.md\", \":p\")) | buffer /srv/x.md | else | edit /srv/x.md | endif\n"
+        );
+        assert_eq!(
+            from_portable("/home/bob").rewrite(&portable),
+            session.replace("/home/alice/", "/home/bob/")
+        );
+
+        let unportable: Vec<(usize, String)> = unportable_paths(&portable, "/home/alice")
+            .into_iter()
+            .map(|path| (*path.number(), path.path().clone()))
+            .collect();
+        assert_eq!(
+            unportable,
+            vec![
+                (3, "/srv/shared/notes.md".to_owned()),
+                (4, "/home/alicia/todo.md".to_owned()),
+                (7, "/srv/x.md".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Sessions kept at the root of a project as `Session.vim`, the file
+//! `:mksession` writes when it is given no name. They are found by walking up
+//! from the working directory to the root of its repository, and in the
+//! projects below the configured workspace roots.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+/// The file name `:mksession` writes by default.
+pub const PROJECT_SESSION_FILE: &str = "Session.vim";
+
+/// Entries marking the root of a version controlled project.
+const VCS_MARKERS: [&str; 4] = [".git", ".hg", ".svn", ".jj"];
+
+/// The project directories holding a `Session.vim` between a directory and the
+/// root of its repository, the innermost first. Outside of a repository only
+/// the directory itself is looked at.
+///
+/// # Arguments
+///     * cwd The directory to start from, usually the working directory.
+#[must_use]
+pub fn discover_upwards(cwd: &Path) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = vec![];
+    for dir in cwd.ancestors() {
+        if dir.join(PROJECT_SESSION_FILE).is_file() {
+            found.push(dir.to_path_buf());
+        }
+        if VCS_MARKERS.iter().any(|marker| dir.join(marker).exists()) {
+            return found;
+        }
+    }
+    found.retain(|dir| dir == cwd);
+    found
+}
+
+/// The projects directly below workspace roots, such as `~/code`, that hold a
+/// `Session.vim`. Roots that can not be read are skipped.
+///
+/// # Arguments
+///     * roots The workspace roots.
+#[must_use]
+pub fn discover_in_workspaces(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = vec![];
+    for root in roots {
+        let entries = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("Skipping workspace root {} => {}", root.display(), e);
+                continue;
+            }
+        };
+        let mut projects: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                !path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .filter(|path| path.join(PROJECT_SESSION_FILE).is_file())
+            .collect();
+        projects.sort();
+        found.extend(projects);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{discover_in_workspaces, discover_upwards, PROJECT_SESSION_FILE};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_discover_stops_at_the_repository_root() {
+        let sandbox = test_dir("project");
+        let root = sandbox.path();
+        let repo = root.join("code").join("api");
+        let nested = repo.join("crates").join("core");
+        fs::create_dir_all(&nested).expect("Failed to create the sandbox");
+        fs::create_dir_all(repo.join(".git")).expect("Failed to create the repository");
+        fs::create_dir_all(root.join("code").join("web")).expect("Failed to create a project");
+        for dir in [root, &repo, &nested, &root.join("code").join("web")] {
+            fs::write(dir.join(PROJECT_SESSION_FILE), "").expect("Failed to write a session");
+        }
+
+        assert_eq!(
+            discover_upwards(&nested),
+            vec![nested.clone(), repo.clone()]
+        );
+        let outside = root.join("code");
+        assert_eq!(discover_upwards(&outside), Vec::<std::path::PathBuf>::new());
+        assert_eq!(
+            discover_in_workspaces(&[root.join("code"), root.join("missing")]),
+            vec![repo, root.join("code").join("web")]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! Picks the stale sessions `vsm prune` offers to delete. Criteria are
+//! combined, a session is only pruned if it matches every one of them.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+use derive_getters::Getters;
+
+use super::dirs::SessionDirs;
+use super::health::SessionHealth;
+use super::history::OpenHistory;
+use super::metadata::MetadataStore;
+use crate::config::PruneConfig;
+use crate::error::VsmRuntimeFault;
+
+/// Units accepted by `parse_age`, and how many seconds they stand for.
+const AGE_UNITS: [(char, u64); 5] = [
+    ('s', 1),
+    ('m', 60),
+    ('h', 3_600),
+    ('d', 86_400),
+    ('w', 604_800),
+];
+
+/// Parses an age such as `30d` into seconds. Accepted units are s, m, h, d
+/// and w.
+///
+/// # Errors
+///     * VsmRuntimeFault::Prune if the age has no unit or is not a number.
+pub fn parse_age(age: &str) -> Result<u64, VsmRuntimeFault> {
+    let invalid = || VsmRuntimeFault::Prune {
+        msg: format!("'{}' is not an age, use e.g. 30d, 12h or 2w", age),
+    };
+    let trimmed = age.trim();
+    let unit = trimmed.chars().last().ok_or_else(invalid)?;
+    let seconds = AGE_UNITS
+        .iter()
+        .find(|&&(u, _)| u == unit)
+        .map(|&(_, seconds)| seconds)
+        .ok_or_else(invalid)?;
+    trimmed
+        .strip_suffix(unit)
+        .unwrap_or_default()
+        .parse::<u64>()
+        .map_err(|_not_a_number| invalid())
+        .map(|amount| amount.saturating_mul(seconds))
+}
+
+/// What the age of a session is measured by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AgeBy {
+    /// When the session file was last written
+    #[default]
+    Mtime,
+    /// When the session was last opened through vsm, sessions that never were
+    /// fall back to their mtime
+    LastOpen,
+}
+
+impl AgeBy {
+    /// Names of all measures, as accepted on the command line and in the
+    /// config.toml.
+    pub const NAMES: [&'static str; 2] = ["mtime", "last-open"];
+}
+
+impl FromStr for AgeBy {
+    type Err = VsmRuntimeFault;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mtime" => Ok(Self::Mtime),
+            "last-open" => Ok(Self::LastOpen),
+            _ => Err(VsmRuntimeFault::Prune {
+                msg: format!("Unknown age measure '{}'", s),
+            }),
+        }
+    }
+}
+
+/// A session that matched every criterion of a policy.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct PruneCandidate {
+    /// Session name
+    name: String,
+    /// Path to the session file
+    path: PathBuf,
+    /// Why the session is pruned, one entry per criterion
+    reasons: Vec<String>,
+}
+
+/// Decides which sessions are stale. Pinned sessions are never pruned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Getters)]
+pub struct PrunePolicy {
+    /// Prune sessions older than this many seconds
+    older_than: Option<u64>,
+    /// What the age is measured by
+    age_by: AgeBy,
+    /// Prune sessions with a missing directory or files
+    broken: bool,
+    /// Prune sessions that were never opened through vsm
+    never_opened: bool,
+    /// Never prune the this many most recently used sessions
+    keep: Option<usize>,
+}
+
+impl PrunePolicy {
+    /// Builds the policy configured in the `[prune]` table of the config.toml.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Prune if the age or its measure is invalid.
+    pub fn from_config(config: &PruneConfig) -> Result<Self, VsmRuntimeFault> {
+        Ok(Self {
+            older_than: config.older_than().as_deref().map(parse_age).transpose()?,
+            age_by: config
+                .age_by()
+                .as_deref()
+                .map(str::parse::<AgeBy>)
+                .transpose()?
+                .unwrap_or_default(),
+            broken: *config.broken(),
+            never_opened: *config.never_opened(),
+            keep: *config.keep(),
+        })
+    }
+
+    /// Prunes sessions older than the given number of seconds.
+    #[must_use]
+    pub const fn with_older_than(mut self, seconds: u64, age_by: AgeBy) -> Self {
+        self.older_than = Some(seconds);
+        self.age_by = age_by;
+        self
+    }
+
+    /// Prunes sessions with a missing directory or files.
+    #[must_use]
+    pub const fn with_broken(mut self) -> Self {
+        self.broken = true;
+        self
+    }
+
+    /// Prunes sessions that were never opened through vsm.
+    #[must_use]
+    pub const fn with_never_opened(mut self) -> Self {
+        self.never_opened = true;
+        self
+    }
+
+    /// Never prunes the given number of most recently used sessions. This
+    /// only spares sessions, on its own it prunes nothing.
+    #[must_use]
+    pub const fn with_keep(mut self, keep: usize) -> Self {
+        self.keep = Some(keep);
+        self
+    }
+
+    /// Returns true if the policy has no criteria, and would prune nothing.
+    /// `keep` is not a criterion.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.older_than.is_none() && !self.broken && !self.never_opened
+    }
+
+    /// The most recently used sessions `keep` spares, most recent first.
+    ///
+    /// # Arguments
+    ///     * sessions Paths to the session files.
+    ///     * dirs The session directories, which name the sessions.
+    ///     * history The open history of the sessions.
+    #[must_use]
+    pub fn kept<'s>(
+        &self,
+        sessions: &'s [PathBuf],
+        dirs: &SessionDirs,
+        history: &OpenHistory,
+    ) -> Vec<&'s PathBuf> {
+        let mut by_use: Vec<&PathBuf> = sessions.iter().collect();
+        by_use.sort_by_key(|session| Reverse(last_used(session, dirs, history)));
+        by_use.truncate(self.keep.unwrap_or_default());
+        by_use
+    }
+
+    /// Picks the sessions that match every criterion of the policy.
+    ///
+    /// # Arguments
+    ///     * sessions Paths to the session files.
+    ///     * dirs The session directories, which name the sessions.
+    ///     * history The open history of the sessions.
+    ///     * metadata The metadata of the sessions, pinned ones are kept.
+    ///     * home The users home directory, see `SessionFile::resolve_path`.
+    ///     * now Current time, in seconds since the unix epoch.
+    #[must_use]
+    pub fn select(
+        &self,
+        sessions: &[PathBuf],
+        dirs: &SessionDirs,
+        history: &OpenHistory,
+        metadata: &MetadataStore,
+        home: &str,
+        now: u64,
+    ) -> Vec<PruneCandidate> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let kept = self.kept(sessions, dirs, history);
+
+        let mut candidates: Vec<PruneCandidate> = vec![];
+        for session in sessions {
+            let key = dirs.key_of(session);
+            if kept.contains(&session) || metadata.get(&key).is_some_and(|m| *m.pinned()) {
+                continue;
+            }
+            let mut reasons: Vec<String> = vec![];
+            if let Some(older_than) = self.older_than {
+                let at = match self.age_by {
+                    AgeBy::Mtime => modified(session),
+                    AgeBy::LastOpen => last_used(session, dirs, history),
+                };
+                let age = now.saturating_sub(at);
+                if age <= older_than {
+                    continue;
+                }
+                reasons.push(format!(
+                    "{} day(s) old",
+                    age.checked_div(86_400).unwrap_or(0)
+                ));
+            }
+            if self.broken {
+                let health = SessionHealth::check(session, home);
+                if health.is_healthy() {
+                    continue;
+                }
+                reasons.extend(health.problems().iter().map(ToString::to_string));
+            }
+            if self.never_opened {
+                if history.get(&key).is_some() {
+                    continue;
+                }
+                reasons.push("never opened".to_owned());
+            }
+            candidates.push(PruneCandidate {
+                name: dirs.name_of(session),
+                path: session.clone(),
+                reasons,
+            });
+        }
+        candidates
+    }
+}
+
+/// When a session was last opened through vsm, or written if it never was,
+/// in seconds since the unix epoch.
+fn last_used(session: &Path, dirs: &SessionDirs, history: &OpenHistory) -> u64 {
+    history
+        .get(&dirs.key_of(session))
+        .map_or_else(|| modified(session), |record| *record.last_opened())
+}
+
+/// When a session file was last written, in seconds since the unix epoch.
+fn modified(session: &Path) -> u64 {
+    fs::metadata(session)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use filetime::{set_file_mtime, FileTime};
+    use pretty_assertions::assert_eq;
+
+    use super::{parse_age, AgeBy, PrunePolicy};
+    use crate::session::{MetadataStore, OpenHistory, SessionDirs};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d").expect("days parse"), 30 * 86_400);
+        assert_eq!(parse_age("2w").expect("weeks parse"), 14 * 86_400);
+        parse_age("30").expect_err("an age needs a unit");
+        parse_age("d").expect_err("an age needs an amount");
+    }
+
+    #[test]
+    fn test_select_combines_criteria() {
+        let sandbox = test_dir("prune");
+        let root = sandbox.path();
+        let day = 86_400;
+        let now = 100 * day;
+        let sessions: Vec<_> = [("old", 10), ("older", 5), ("new", 99), ("pinned", 1)]
+            .iter()
+            .map(|&(name, days)| {
+                let path = root.join(format!("{}.vim", name));
+                let mtime = i64::try_from(days * day).expect("mtime fits");
+                fs::write(&path, "")
+                    .and_then(|()| set_file_mtime(&path, FileTime::from_unix_time(mtime, 0)))
+                    .expect("session is written");
+                path
+            })
+            .collect();
+        let mut history = OpenHistory::load(&root.join("history.toml")).expect("empty history");
+        history.record("old", now, Some(0));
+        let mut metadata = MetadataStore::load(&root.join("metadata.toml")).expect("empty store");
+        metadata.entry("pinned").set_pinned(true);
+
+        let dirs = SessionDirs::from_list(&root.to_string_lossy());
+        let names = |policy: &PrunePolicy| -> Vec<String> {
+            policy
+                .select(&sessions, &dirs, &history, &metadata, "/home", now)
+                .into_iter()
+                .map(|candidate| candidate.name().clone())
+                .collect()
+        };
+        let by_mtime = PrunePolicy::default().with_older_than(30 * day, AgeBy::Mtime);
+        assert_eq!(names(&by_mtime), vec!["old", "older"]);
+        let by_open = PrunePolicy::default().with_older_than(30 * day, AgeBy::LastOpen);
+        assert_eq!(names(&by_open), vec!["older"]);
+        assert_eq!(names(&by_mtime.with_keep(2)), vec!["older"]);
+        // Keeping sessions is no criterion of its own
+        assert!(PrunePolicy::default().with_keep(2).is_empty());
+        assert!(names(&PrunePolicy::default().with_keep(2)).is_empty());
+        assert!(names(&PrunePolicy::default()).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! A snapshot of a session file's details on disk.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use std::{fs, io};
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use super::dirs::SessionDirs;
+
+/// Describes a single session file, used wherever sessions are reported to the
+/// user or to other programs.
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct SessionRecord {
+    /// File name without the `.vim` extension, prefixed by the label of its
+    /// directory outside the primary one
+    name: String,
+    /// Key of the metadata and history of the session, see
+    /// `SessionDirs::key_of`
+    #[serde(skip)]
+    key: String,
+    /// Label of the session directory the file is kept in
+    source: String,
+    /// The session is a `Session.vim` kept at a project root
+    project_local: bool,
+    /// Absolute path to the session file
+    path: PathBuf,
+    /// Size of the session file in bytes
+    size: u64,
+    /// Last modification time, in seconds since the unix epoch
+    mtime: u64,
+}
+
+impl SessionRecord {
+    /// Builds a new SessionRecord by reading the file metadata, its directory
+    /// is the only session directory.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///
+    /// # Errors
+    ///     * io::Error if the file metadata can not be read.
+    pub fn from_path(session: &Path) -> Result<Self, io::Error> {
+        let parent = session.parent().unwrap_or_else(|| Path::new(""));
+        Self::from_path_in(session, &SessionDirs::from_list(&parent.to_string_lossy()))
+    }
+
+    /// Builds a new SessionRecord by reading the file metadata, naming it
+    /// after the session directory it is kept in.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///     * dirs The session directories.
+    ///
+    /// # Errors
+    ///     * io::Error if the file metadata can not be read.
+    pub fn from_path_in(session: &Path, dirs: &SessionDirs) -> Result<Self, io::Error> {
+        let metadata = fs::metadata(session)?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Ok(Self {
+            name: dirs.name_of(session),
+            key: dirs.key_of(session),
+            source: dirs
+                .source(session)
+                .map(|dir| dir.label().clone())
+                .unwrap_or_default(),
+            project_local: dirs.is_project_local(session),
+            path: fs::canonicalize(session).unwrap_or_else(|_| session.to_path_buf()),
+            size: metadata.len(),
+            mtime,
+        })
+    }
+}
@@ -0,0 +1,381 @@
+//! Rewrites the paths a session refers to after a project moved, such as
+//! from `~/src/x` to `~/code/x`. Only the lines that name a directory or a
+//! file are touched, so the rest of the session stays as it was written.
+
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+
+use derive_getters::Getters;
+
+use crate::error::VsmRuntimeFault;
+
+/// Commands whose argument is a path, with the `$` `:mksession` puts before
+/// `argadd` stripped.
+const PATH_COMMANDS: [&str; 12] = [
+    "cd", "chdir", "lcd", "lchdir", "tcd", "tchdir", "badd", "balt", "edit", "tabedit", "tabnew",
+    "argadd",
+];
+
+/// How `:mksession` starts the line that shows a file in a window,
+/// `if bufexists(fnamemodify("f", ":p")) | buffer f | else | edit f | endif`.
+const BUFFER_SWITCH: &str = "if bufexists(";
+
+/// Characters `fnameescape()` puts a backslash before, `:mksession` writes
+/// every path through it.
+const FNAME_SPECIAL: &str = " \t\n*?[{`$\\%#'\"|!<";
+
+/// Directory of the backups inside the data directory.
+#[cfg(feature = "cli")]
+const BACKUP_DIR: &str = "backups";
+
+/// A line of a session that relocating changes.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct LineChange {
+    /// Line number, starting at 1
+    number: usize,
+    /// The line as it is
+    before: String,
+    /// The line once relocated
+    after: String,
+}
+
+/// Moves the paths under one directory to another.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct Relocation {
+    /// Directory prefixes and what they become, as given and in the other of
+    /// the `~` and `$HOME` spellings
+    #[getter(skip)]
+    prefixes: Vec<(String, String)>,
+    /// Directory the paths are moved from
+    from: String,
+    /// Directory the paths are moved to
+    to: String,
+}
+
+impl Relocation {
+    /// Builds a relocation. Paths written with `~` are matched as well as
+    /// absolute ones under `home`, whichever spelling the prefix was given in.
+    ///
+    /// # Arguments
+    ///     * from Directory the paths are moved from.
+    ///     * to Directory the paths are moved to.
+    ///     * home The home directory `~` stands for.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Relocate if a directory is empty, or both are the
+    ///       same.
+    #[cfg(feature = "cli")]
+    pub fn new(from: &str, to: &str, home: &str) -> Result<Self, VsmRuntimeFault> {
+        let from = from.trim_end_matches('/');
+        let to = to.trim_end_matches('/');
+        if from.is_empty() || to.is_empty() {
+            return Err(VsmRuntimeFault::Relocate {
+                msg: "Both directories must be given, and neither can be /".to_owned(),
+            });
+        }
+        let (absolute_from, absolute_to) = (expand_home(from, home), expand_home(to, home));
+        if absolute_from == absolute_to {
+            return Err(VsmRuntimeFault::Relocate {
+                msg: format!("{} is moved onto itself", from),
+            });
+        }
+        // Each spelling of the old directory becomes the same spelling of the new one
+        let mut prefixes: Vec<(String, String)> = vec![];
+        if let Some(tilde_from) = contract_home(&absolute_from, home) {
+            let tilde_to = contract_home(&absolute_to, home).unwrap_or_else(|| absolute_to.clone());
+            prefixes.push((tilde_from, tilde_to));
+        }
+        prefixes.push((absolute_from, absolute_to));
+        Ok(Self {
+            prefixes,
+            from: from.to_owned(),
+            to: to.to_owned(),
+        })
+    }
+
+    /// Builds a relocation from directory prefixes as they are, the first
+    /// pair names it.
+    pub(super) fn from_prefixes(prefixes: Vec<(String, String)>) -> Self {
+        let (from, to) = prefixes.first().cloned().unwrap_or_default();
+        Self { prefixes, from, to }
+    }
+
+    /// The lines of a session that relocating changes.
+    ///
+    /// # Arguments
+    ///     * contents The vim script written by `:mksession`.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn changes(&self, contents: &str) -> Vec<LineChange> {
+        contents
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                self.relocate_line(line).map(|after| LineChange {
+                    number: idx.saturating_add(1),
+                    before: line.to_owned(),
+                    after,
+                })
+            })
+            .collect()
+    }
+
+    /// Relocates the paths of a session.
+    ///
+    /// # Arguments
+    ///     * contents The vim script written by `:mksession`.
+    #[must_use]
+    pub fn rewrite(&self, contents: &str) -> String {
+        contents
+            .split_inclusive('\n')
+            .map(|line| {
+                let (text, ending) = line
+                    .strip_suffix('\n')
+                    .map_or((line, ""), |text| (text, "\n"));
+                self.relocate_line(text)
+                    .map_or_else(|| line.to_owned(), |after| format!("{}{}", after, ending))
+            })
+            .collect()
+    }
+
+    /// Relocates the paths of a session file in place, it is only written
+    /// when a path changed.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///
+    /// # Returns
+    ///     * true if the session was rewritten.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Relocate if the session can not be read or
+    ///       written.
+    pub fn apply_to(&self, session: &Path) -> Result<bool, VsmRuntimeFault> {
+        let fault = |e: std::io::Error| VsmRuntimeFault::Relocate {
+            msg: format!("{} => {}", session.display(), e),
+        };
+        let contents = fs::read_to_string(session).map_err(fault)?;
+        let rewritten = self.rewrite(&contents);
+        if rewritten == contents {
+            return Ok(false);
+        }
+        fs::write(session, rewritten).map_err(fault)?;
+        Ok(true)
+    }
+
+    /// The relocated line, if it names a path that moved.
+    fn relocate_line(&self, line: &str) -> Option<String> {
+        if !is_path_line(line) {
+            return None;
+        }
+        let after = self
+            .prefixes
+            .iter()
+            .fold(line.to_owned(), |relocated, prefix| {
+                replace_path_prefix(&relocated, &prefix.0, &prefix.1)
+            });
+        (after != line).then_some(after)
+    }
+}
+
+/// Copies a session into the backups of the data directory before it is
+/// rewritten.
+///
+/// # Arguments
+///     * session Path to the session file.
+///     * name Name of the session.
+///     * data_dir The vsm data directory.
+///     * now Seconds since the unix epoch, tells backups of a session apart.
+///
+/// # Returns
+///     * The path of the backup.
+///
+/// # Errors
+///     * VsmRuntimeFault::Relocate if the backup can not be written.
+#[cfg(feature = "cli")]
+pub fn backup_session(
+    session: &Path,
+    name: &str,
+    data_dir: &Path,
+    now: u64,
+) -> Result<PathBuf, VsmRuntimeFault> {
+    let dir = data_dir.join(BACKUP_DIR);
+    // Grouped and labelled names are kept flat
+    let flat = name.replace(['/', ':'], "-");
+    let backup = dir.join(format!("{}.{}.vim", flat, now));
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::copy(session, &backup))
+        .map_err(|e| VsmRuntimeFault::Relocate {
+            msg: format!("{} => {}", backup.display(), e),
+        })?;
+    Ok(backup)
+}
+
+/// Returns true if the line runs a command whose argument is a path, or
+/// shows a file in a window.
+pub(super) fn is_path_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with(BUFFER_SWITCH)
+        || line
+            .split_whitespace()
+            .next()
+            .map(|command| command.trim_start_matches('$'))
+            .is_some_and(|command| PATH_COMMANDS.contains(&command))
+}
+
+/// The path a line names, escaped as `:mksession` wrote it. None if the line
+/// names no path.
+#[cfg(feature = "cli")]
+pub(super) fn path_argument(line: &str) -> Option<&str> {
+    if !is_path_line(line) {
+        return None;
+    }
+    let line = line.trim();
+    if line.starts_with(BUFFER_SWITCH) {
+        return line
+            .split(" | ")
+            .find_map(|segment| segment.trim().strip_prefix("buffer "))
+            .map(str::trim);
+    }
+    // `badd +12 path`, the line number is optional
+    let argument = line.split_once(' ')?.1.trim_start();
+    let path = match argument.split_once(' ') {
+        Some((line_number, path)) if line_number.starts_with('+') => path,
+        _ => argument,
+    };
+    (!path.is_empty()).then_some(path)
+}
+
+/// Escapes a path the way `fnameescape()` does.
+pub(super) fn fname_escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if FNAME_SPECIAL.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Spells a directory starting with `~` with the home directory instead.
+#[cfg(feature = "cli")]
+fn expand_home(dir: &str, home: &str) -> String {
+    match dir.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home.trim_end_matches('/'), rest)
+        }
+        _ => dir.to_owned(),
+    }
+}
+
+/// Spells a directory under the home directory with `~` instead. None if it
+/// is outside of the home directory.
+#[cfg(feature = "cli")]
+pub(super) fn contract_home(dir: &str, home: &str) -> Option<String> {
+    let home = home.trim_end_matches('/');
+    let rest = dir.strip_prefix(home)?;
+    (!home.is_empty() && (rest.is_empty() || rest.starts_with('/'))).then(|| format!("~{}", rest))
+}
+
+/// Replaces a directory at the start of every path in a session. A path only
+/// matches when the directory is followed by a separator or ends the path,
+/// so `/home/al` does not rewrite `/home/alice`. Both directories are
+/// escaped the way `:mksession` writes paths.
+pub(super) fn replace_path_prefix(contents: &str, from: &str, to: &str) -> String {
+    let from = fname_escape(from.trim_end_matches('/'));
+    let to = fname_escape(to.trim_end_matches('/'));
+    let (from, to) = (from.as_str(), to.as_str());
+    if from.is_empty() || from == to {
+        return contents.to_owned();
+    }
+    let mut rewritten = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(at) = rest.find(from) {
+        let (before, matched) = rest.split_at(at);
+        let after = matched.get(from.len()..).unwrap_or_default();
+        let starts_path = before.chars().last().is_none_or(|c| !is_path_char(c));
+        let ends_dir = after.chars().next().is_none_or(|c| !is_path_char(c));
+        rewritten.push_str(before);
+        rewritten.push_str(if starts_path && ends_dir { to } else { from });
+        rest = after;
+    }
+    rewritten.push_str(rest);
+    rewritten
+}
+
+/// Returns true if a character can be part of a directory name.
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '~' | '\\')
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::Relocation;
+    use crate::testing::VIM_SESSION;
+
+    #[test]
+    fn test_rewrite_touches_path_commands_only() {
+        let session = "cd ~/src/x\n\
+                       badd +12 ~/src/x/main.rs\n\
+                       $argadd /home/al/src/x/lib.rs\n\
+                       edit /home/al/src/xyz/other.rs\n\
+                       tcd ~/src/x/docs\n\
+                       let g:notes = '~/src/x/notes'\n";
+        let relocation =
+            Relocation::new("/home/al/src/x/", "~/code/x", "/home/al").expect("prefixes differ");
+        assert_eq!(
+            relocation.rewrite(session),
+            "cd ~/code/x\n\
+             badd +12 ~/code/x/main.rs\n\
+             $argadd /home/al/code/x/lib.rs\n\
+             edit /home/al/src/xyz/other.rs\n\
+             tcd ~/code/x/docs\n\
+             let g:notes = '~/src/x/notes'\n"
+        );
+        let numbers: Vec<usize> = relocation
+            .changes(session)
+            .iter()
+            .map(|change| *change.number())
+            .collect();
+        assert_eq!(numbers, vec![1, 2, 3, 5]);
+        Relocation::new("~/src/x", "/home/al/src/x/", "/home/al").expect_err("nothing moves");
+    }
+
+    #[test]
+    fn test_rewrite_moves_window_files_and_alternate_buffers() {
+        // Vim writes absolute paths for files outside of the working directory
+        let session = VIM_SESSION.replace("src/", "~/code/vsm/src/");
+        let relocation =
+            Relocation::new("~/code/vsm", "~/work/vsm", "/home/al").expect("prefixes differ");
+        let relocated = relocation.rewrite(&session);
+        assert!(!relocated.contains("~/code/vsm"));
+        assert!(relocated.contains(
+            "if bufexists(fnamemodify(\"~/work/vsm/src/my\\ app.rs\", \":p\")) \
+             | buffer ~/work/vsm/src/my\\ app.rs | else | edit ~/work/vsm/src/my\\ app.rs | endif"
+        ));
+        assert_eq!(relocated.lines().count(), session.lines().count());
+    }
+
+    #[test]
+    fn test_rewrite_matches_escaped_paths() {
+        let session = "cd ~/my\\ src/x\n\
+                       balt ~/my\\ src/x/main.rs\n\
+                       badd +1 ~/my\\ src/xyz.rs\n\
+                       badd +1 ~/my\\ srcs/x.rs\n";
+        let relocation =
+            Relocation::new("~/my src/x", "~/our src#/x", "/home/al").expect("prefixes differ");
+        assert_eq!(
+            relocation.rewrite(session),
+            "cd ~/our\\ src\\#/x\n\
+             balt ~/our\\ src\\#/x/main.rs\n\
+             badd +1 ~/my\\ src/xyz.rs\n\
+             badd +1 ~/my\\ srcs/x.rs\n"
+        );
+    }
+}
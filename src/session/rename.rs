@@ -0,0 +1,233 @@
+//! Renames or moves a session together with the extra file `:mksession`
+//! sources alongside it, and the references the session holds to its own path.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::companion::{companion_of, companion_path};
+use super::dirs::LABEL_SEPARATOR;
+use crate::error::VsmRuntimeFault;
+
+/// Checks that a name can be used as a session file name.
+///
+/// # Errors
+///     * VsmRuntimeFault::SessionName if the name is empty, hidden, or holds a
+///       path separator or a control character.
+pub fn validate_session_name(name: &str) -> Result<(), VsmRuntimeFault> {
+    let reason = if name.trim().is_empty() {
+        Some("it is empty")
+    } else if name.starts_with('.') {
+        Some("it starts with a dot")
+    } else if name.contains('/') || name.contains('\\') {
+        Some("it holds a path separator")
+    } else if name.contains(LABEL_SEPARATOR) {
+        Some("it holds a ':', which separates the label of a session directory")
+    } else if name.chars().any(char::is_control) {
+        Some("it holds a control character")
+    } else {
+        None
+    };
+    reason.map_or(Ok(()), |reason| {
+        Err(VsmRuntimeFault::SessionName {
+            msg: format!("'{}' can not be used, {}", name, reason),
+        })
+    })
+}
+
+/// Moves a session file to another path, such as into another group. The
+/// directory of the target is created, the companion `x.vim` file moves
+/// along, and `v:this_session` assignments naming the old path or file are
+/// rewritten. Nothing is ever overwritten, a session in the way has to be
+/// moved out of it first.
+///
+/// # Arguments
+///     * session Path to the session file.
+///     * target Path the session file is moved to.
+///
+/// # Returns
+///     * The path of the moved session.
+///
+/// # Errors
+///     * VsmRuntimeFault::SessionRename if the target, or the path its
+///       companion would move to, is taken, or the files can not be moved.
+pub fn move_session(session: &Path, target: &Path) -> Result<PathBuf, VsmRuntimeFault> {
+    let stem = |path: &Path| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let old_name = stem(session);
+    let new_name = stem(target);
+    if session == target {
+        return Err(VsmRuntimeFault::SessionRename {
+            msg: format!("'{}' already has that name", old_name),
+        });
+    }
+
+    let companion = companion_of(session);
+    let target_companion = companion_path(target);
+    if target.exists() {
+        return Err(VsmRuntimeFault::SessionRename {
+            msg: format!(
+                "'{}' already exists, pass --force to overwrite it",
+                new_name
+            ),
+        });
+    }
+    if companion.is_some() && target_companion.exists() {
+        return Err(VsmRuntimeFault::SessionRename {
+            msg: format!(
+                "{} is in the way of the companion of '{}'",
+                target_companion.display(),
+                old_name
+            ),
+        });
+    }
+
+    let io_fault = |path: &Path, e: std::io::Error| VsmRuntimeFault::SessionRename {
+        msg: format!("{} => {}", path.display(), e),
+    };
+    let contents = fs::read_to_string(session).map_err(|e| io_fault(session, e))?;
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| io_fault(dir, e))?;
+    }
+    fs::rename(session, target).map_err(|e| io_fault(session, e))?;
+    let rewritten = replace_this_session(
+        &contents,
+        &session.to_string_lossy(),
+        &target.to_string_lossy(),
+    );
+    let rewritten = rewrite_this_session(&rewritten, &old_name, &new_name);
+    if rewritten != contents {
+        fs::write(target, rewritten).map_err(|e| io_fault(target, e))?;
+    }
+
+    if let Some(companion) = companion {
+        fs::rename(&companion, &target_companion).map_err(|e| io_fault(&companion, e))?;
+    }
+    Ok(target.to_path_buf())
+}
+
+/// Rewrites the `v:this_session` assignments that name the old session file.
+/// The default `expand("<sfile>:p")` assignment follows the file by itself
+/// and is left alone.
+pub(super) fn rewrite_this_session(contents: &str, old_name: &str, new_name: &str) -> String {
+    replace_this_session(
+        contents,
+        &format!("{}.vim", old_name),
+        &format!("{}.vim", new_name),
+    )
+}
+
+/// Replaces a whole file name or path in the `v:this_session` assignments.
+fn replace_this_session(contents: &str, old_file: &str, new_file: &str) -> String {
+    if old_file == new_file {
+        return contents.to_owned();
+    }
+    contents
+        .split_inclusive('\n')
+        .map(|line| {
+            let assignment = line.trim_start().starts_with("let v:this_session");
+            if !assignment || !line.contains(old_file) {
+                return line.to_owned();
+            }
+            let mut rewritten = String::new();
+            let mut pieces = line.split(old_file).peekable();
+            while let Some(piece) = pieces.next() {
+                rewritten.push_str(piece);
+                if pieces.peek().is_some() {
+                    // Only replace whole file names, `myold.vim` is left as is
+                    let whole = piece
+                        .chars()
+                        .last()
+                        .is_none_or(|c| matches!(c, '/' | '\'' | '"' | '=' | ' '));
+                    rewritten.push_str(if whole { new_file } else { old_file });
+                }
+            }
+            rewritten
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{move_session, rewrite_this_session, validate_session_name};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_validate_session_name() {
+        validate_session_name("work-api").expect("name is accepted");
+        for invalid in ["", " ", ".hidden", "a/b", "team:api", "tab\there"] {
+            validate_session_name(invalid).expect_err("name is rejected");
+        }
+    }
+
+    #[test]
+    fn test_rewrite_this_session() {
+        let contents = "let v:this_session=expand(\"<sfile>:p\")\n\
+                        let v:this_session = '/s/old.vim'\n\
+                        let v:this_session = '/s/bold.vim'\n\
+                        edit old.vim\n";
+        assert_eq!(
+            rewrite_this_session(contents, "old", "new"),
+            "let v:this_session=expand(\"<sfile>:p\")\n\
+             let v:this_session = '/s/new.vim'\n\
+             let v:this_session = '/s/bold.vim'\n\
+             edit old.vim\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_moves_companion_and_refuses_overwrite() {
+        let sandbox = test_dir("rename");
+        let root = sandbox.path();
+        let session = root.join("old.vim");
+        let this_session = format!("let v:this_session = '{}'\n", session.display());
+        fs::write(&session, &this_session).expect("Failed to write the session");
+        fs::write(root.join("oldx.vim"), "").expect("Failed to write the companion");
+        fs::write(root.join("taken.vim"), "").expect("Failed to write a session");
+        fs::write(root.join("movedx.vim"), "let SessionLoad = 1\n")
+            .expect("Failed to write a session");
+
+        move_session(&session, &root.join("taken.vim")).expect_err("taken name is refused");
+        move_session(&session, &root.join("moved.vim"))
+            .expect_err("a session in the way of the companion is refused");
+        assert!(root.join("movedx.vim").exists());
+        let renamed = move_session(&session, &root.join("new.vim")).expect("session is renamed");
+        assert_eq!(renamed, root.join("new.vim"));
+        assert!(!session.exists());
+        assert!(root.join("newx.vim").exists());
+        assert_eq!(
+            fs::read_to_string(&renamed).expect("Failed to read the session"),
+            format!("let v:this_session = '{}'\n", renamed.display())
+        );
+    }
+
+    #[test]
+    fn test_move_session_into_another_group() {
+        let sandbox = test_dir("move");
+        let root = sandbox.path();
+        fs::create_dir_all(root.join("work")).expect("Failed to create the sandbox");
+        let session = root.join("work/api.vim");
+        fs::write(
+            &session,
+            format!("let v:this_session = '{}'\n", session.display()),
+        )
+        .expect("Failed to write the session");
+        fs::write(root.join("work/apix.vim"), "").expect("Failed to write the companion");
+
+        let target = root.join("oss/api.vim");
+        let moved = move_session(&session, &target).expect("session is moved");
+        assert_eq!(moved, target);
+        assert!(root.join("oss/apix.vim").exists());
+        assert!(!root.join("work/apix.vim").exists());
+        assert_eq!(
+            fs::read_to_string(&moved).expect("Failed to read the session"),
+            format!("let v:this_session = '{}'\n", target.display())
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Orders in which sessions are listed and offered, by name, by when they
+//! were modified, or by the open history.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+use super::dirs::SessionDirs;
+use super::history::{OpenHistory, OpenRecord};
+use crate::error::VsmRuntimeFault;
+
+/// Seconds in a day.
+const DAY: u64 = 86_400;
+
+/// Frecency weight of an open, by how many days ago the session was last
+/// opened. Older opens count for less.
+const FRECENCY_WEIGHTS: [(u64, u64); 4] = [(4, 100), (14, 70), (31, 50), (90, 30)];
+
+/// Frecency weight of sessions last opened longer ago than any of the
+/// `FRECENCY_WEIGHTS`.
+const STALE_WEIGHT: u64 = 10;
+
+/// Orders in which sessions can be listed and offered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionSort {
+    /// Most recently opened first, never opened sessions last
+    Mru,
+    /// Most frequently and recently opened first
+    Frecency,
+    /// Alphabetically
+    #[default]
+    Name,
+    /// Most recently modified first
+    Mtime,
+}
+
+impl SessionSort {
+    /// Names of all orders, as accepted on the command line.
+    pub const NAMES: [&'static str; 4] = ["mru", "frecency", "name", "mtime"];
+
+    /// Sorts session files in this order, ties are broken by name.
+    ///
+    /// # Arguments
+    ///     * sessions Paths to the session files.
+    ///     * dirs The session directories, which name the sessions.
+    ///     * history The open history of the sessions.
+    ///     * now Current time, in seconds since the unix epoch.
+    pub fn sort(
+        self,
+        sessions: &mut [PathBuf],
+        dirs: &SessionDirs,
+        history: &OpenHistory,
+        now: u64,
+    ) {
+        sessions.sort_by_key(|session| dirs.name_of(session));
+        match self {
+            Self::Mru => sessions.sort_by_key(|session| {
+                Reverse(
+                    history
+                        .get(&dirs.key_of(session))
+                        .map(|record| *record.last_opened()),
+                )
+            }),
+            Self::Frecency => sessions.sort_by_key(|session| {
+                Reverse(
+                    history
+                        .get(&dirs.key_of(session))
+                        .map_or(0, |record| frecency(record, now)),
+                )
+            }),
+            Self::Name => {}
+            Self::Mtime => sessions.sort_by_key(|session| {
+                Reverse(
+                    fs::metadata(session)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok()),
+                )
+            }),
+        }
+    }
+}
+
+impl FromStr for SessionSort {
+    type Err = VsmRuntimeFault;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mru" => Ok(Self::Mru),
+            "frecency" => Ok(Self::Frecency),
+            "name" => Ok(Self::Name),
+            "mtime" => Ok(Self::Mtime),
+            _ => Err(VsmRuntimeFault::History {
+                msg: format!("Unknown sort order '{}'", s),
+            }),
+        }
+    }
+}
+
+/// Scores a record by how often and how recently the session was opened,
+/// higher is better.
+///
+/// # Arguments
+///     * record The open record of the session.
+///     * now Current time, in seconds since the unix epoch.
+fn frecency(record: &OpenRecord, now: u64) -> u64 {
+    let days = now
+        .saturating_sub(*record.last_opened())
+        .checked_div(DAY)
+        .unwrap_or_default();
+    let weight = FRECENCY_WEIGHTS
+        .iter()
+        .find(|&&(within, _)| days < within)
+        .map_or(STALE_WEIGHT, |&(_, weight)| weight);
+    record.count().saturating_mul(weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{SessionSort, DAY};
+    use crate::session::{OpenHistory, SessionDirs};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_sort_orders_sessions_by_history() {
+        let sandbox = test_dir("sort");
+        let mut history =
+            OpenHistory::load(&sandbox.path().join("history.toml")).expect("history is empty");
+        let now = 100 * DAY;
+        // api was opened often but long ago, web once just now
+        for _ in 0..5 {
+            history.record("api", 10 * DAY, Some(0));
+        }
+        history.record("web", now, Some(1));
+
+        let dirs = SessionDirs::from_list("/s");
+        let mut sessions: Vec<PathBuf> = ["zsh", "web", "api"]
+            .iter()
+            .map(|name| PathBuf::from(format!("/s/{}.vim", name)))
+            .collect();
+        SessionSort::Mru.sort(&mut sessions, &dirs, &history, now);
+        assert_eq!(
+            sessions,
+            vec![
+                PathBuf::from("/s/web.vim"),
+                PathBuf::from("/s/api.vim"),
+                PathBuf::from("/s/zsh.vim")
+            ]
+        );
+        SessionSort::Name.sort(&mut sessions, &dirs, &history, now);
+        assert_eq!(sessions.first(), Some(&PathBuf::from("/s/api.vim")));
+        // 5 opens at the stale weight still lose to 1 recent open
+        SessionSort::Frecency.sort(&mut sessions, &dirs, &history, now);
+        assert_eq!(sessions.first(), Some(&PathBuf::from("/s/web.vim")));
+    }
+}
@@ -0,0 +1,132 @@
+//! Builds the startup commands vsm hands to the editor, so sessions are
+//! written by the editor itself without any vimrc wiring.
+
+use std::path::Path;
+
+/// Quotes a string as a vim script literal string.
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// The ex command that writes the session into a file, given as a vim
+/// script literal string.
+fn save_command(session: &str) -> String {
+    format!("execute 'mksession! ' . fnameescape({})", session)
+}
+
+/// Ex commands that make the editor treat a file as its session, and write
+/// the session into it when the editor exits.
+///
+/// # Arguments
+///     * session_file Path the session is written to.
+#[must_use]
+pub fn save_on_exit_commands(session_file: &Path) -> Vec<String> {
+    let session = literal(&session_file.to_string_lossy());
+    vec![
+        format!("let v:this_session = {}", session),
+        format!("autocmd VimLeavePre * {}", save_command(&session)),
+    ]
+}
+
+/// Ex commands that rewrite a session on exit, and optionally on a timer and
+/// whenever a buffer is entered.
+///
+/// # Arguments
+///     * session_file Path the session is written to.
+///     * interval Also save every this many seconds, 0 disables the timer.
+///     * buf_enter Also save whenever a buffer is entered.
+#[must_use]
+pub fn autosave_commands(session_file: &Path, interval: u64, buf_enter: bool) -> Vec<String> {
+    let session = literal(&session_file.to_string_lossy());
+    let mut commands = save_on_exit_commands(session_file);
+    if interval > 0 {
+        commands.push(format!(
+            "call timer_start({}, {{-> execute(\"silent! mksession! \" . fnameescape({}))}}, \
+             {{'repeat': -1}})",
+            interval.saturating_mul(1000),
+            session
+        ));
+    }
+    if buf_enter {
+        commands.push(format!(
+            "autocmd BufEnter * silent! {}",
+            save_command(&session)
+        ));
+    }
+    commands
+}
+
+/// Builds the arguments that start an editor with ex commands and files.
+/// Flags that come before `-S` in the shell command of the variant, such as
+/// the `--` neovide needs to pass flags on to nvim, are kept in front.
+///
+/// # Arguments
+///     * shell_command Shell command of the variant, see `VimVariant`.
+///     * commands Ex commands to run after startup, each given with `-c`.
+///     * files Files to open.
+#[must_use]
+pub fn editor_arguments(shell_command: &str, commands: &[String], files: &[String]) -> Vec<String> {
+    let mut flags: Vec<&str> = shell_command.split_whitespace().collect();
+    flags.pop();
+    let mut arguments: Vec<String> = flags.into_iter().map(str::to_owned).collect();
+    for command in commands {
+        arguments.push("-c".to_owned());
+        arguments.push(command.clone());
+    }
+    arguments.extend(files.iter().cloned());
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{autosave_commands, editor_arguments, save_on_exit_commands};
+
+    #[test]
+    fn test_save_on_exit_commands_quote_the_path() {
+        assert_eq!(
+            save_on_exit_commands(Path::new("/s/it's.vim")),
+            vec![
+                "let v:this_session = '/s/it''s.vim'".to_owned(),
+                "autocmd VimLeavePre * execute 'mksession! ' . fnameescape('/s/it''s.vim')"
+                    .to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_autosave_commands_add_timer_and_buf_enter() {
+        let session = Path::new("/s/api.vim");
+        assert_eq!(autosave_commands(session, 0, false).len(), 2);
+        let commands = autosave_commands(session, 90, true);
+        assert_eq!(commands.len(), 4);
+        assert_eq!(
+            commands.get(2).map(String::as_str),
+            Some(
+                "call timer_start(90000, {-> execute(\"silent! mksession! \" . \
+                 fnameescape('/s/api.vim'))}, {'repeat': -1})"
+            )
+        );
+        assert_eq!(
+            commands.get(3).map(String::as_str),
+            Some("autocmd BufEnter * silent! execute 'mksession! ' . fnameescape('/s/api.vim')")
+        );
+    }
+
+    #[test]
+    fn test_editor_arguments_keep_passthrough_flags() {
+        let commands = vec!["echo 1".to_owned()];
+        let files = vec!["a.rs".to_owned()];
+        assert_eq!(
+            editor_arguments("-S", &commands, &files),
+            vec!["-c", "echo 1", "a.rs"]
+        );
+        assert_eq!(
+            editor_arguments("-- -S", &commands, &[]),
+            vec!["--", "-c", "echo 1"]
+        );
+    }
+}
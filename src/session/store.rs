@@ -0,0 +1,143 @@
+//! A keyed toml store in the vsm data directory. Backs both the session
+//! metadata and the open history, which only differ in what they keep per
+//! session and how their errors are reported.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::VsmRuntimeFault;
+
+/// What a keyed store keeps for every session.
+pub trait Stored: Clone + Default + Serialize + DeserializeOwned {
+    /// Name of the store file inside the vsm data directory.
+    const FILE: &'static str;
+
+    /// Wraps a message in the fault the store reports.
+    fn fault(msg: String) -> VsmRuntimeFault;
+
+    /// Returns true if the entry holds nothing worth writing.
+    fn is_blank(&self) -> bool {
+        false
+    }
+}
+
+/// The on disk layout of a store.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct StoreFile<T> {
+    /// Entry of every session, keyed by session key
+    #[serde(default = "BTreeMap::new")]
+    sessions: BTreeMap<String, T>,
+}
+
+/// Entries of all sessions, read from and written to a single toml file.
+#[derive(Debug)]
+pub struct KeyedStore<T> {
+    /// Path to the store file
+    path: PathBuf,
+    /// Entry of every session, keyed by session key
+    sessions: BTreeMap<String, T>,
+}
+
+impl<T: Stored> KeyedStore<T> {
+    /// Reads the store kept in a vsm data directory.
+    ///
+    /// # Arguments
+    ///     * data_dir The vsm data directory.
+    ///
+    /// # Errors
+    ///     * The fault of T if the file exists but can not be read or
+    ///       parsed.
+    pub fn load_from_dir(data_dir: &Path) -> Result<Self, VsmRuntimeFault> {
+        Self::load(&data_dir.join(T::FILE))
+    }
+
+    /// Reads the store, a missing file is an empty store.
+    ///
+    /// # Arguments
+    ///     * path Path to the store file.
+    ///
+    /// # Errors
+    ///     * The fault of T if the file exists but can not be read or
+    ///       parsed.
+    pub fn load(path: &Path) -> Result<Self, VsmRuntimeFault> {
+        let sessions = if path.exists() {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| T::fault(format!("{} => {}", path.display(), e)))?;
+            toml::from_str::<StoreFile<T>>(&contents)
+                .map_err(|e| T::fault(format!("{} => {}", path.display(), e)))?
+                .sessions
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            sessions,
+        })
+    }
+
+    /// Writes the store, creating the data directory if needed. Blank
+    /// entries are left out.
+    ///
+    /// # Errors
+    ///     * The fault of T if the file can not be written.
+    pub fn save(&self) -> Result<(), VsmRuntimeFault> {
+        let io_fault = |e: io::Error| T::fault(format!("{} => {}", self.path.display(), e));
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_fault)?;
+        }
+        let file = StoreFile {
+            sessions: self
+                .sessions
+                .iter()
+                .filter(|&(_, entry)| !entry.is_blank())
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect(),
+        };
+        let contents = toml::to_string(&file)
+            .map_err(|e| T::fault(format!("{} => {}", self.path.display(), e)))?;
+        fs::write(&self.path, contents).map_err(io_fault)
+    }
+
+    /// Entry of every session, keyed by session key.
+    #[cfg(any(test, feature = "cli"))]
+    #[must_use]
+    pub const fn sessions(&self) -> &BTreeMap<String, T> {
+        &self.sessions
+    }
+
+    /// The entry of a session, if there is one.
+    #[must_use]
+    pub fn get(&self, session: &str) -> Option<&T> {
+        self.sessions.get(session)
+    }
+
+    /// The entry of a session for editing, created if needed.
+    pub fn entry(&mut self, session: &str) -> &mut T {
+        self.sessions.entry(session.to_owned()).or_default()
+    }
+
+    /// Replaces the entry of a session.
+    #[cfg(feature = "cli")]
+    pub fn insert(&mut self, session: &str, entry: T) {
+        self.sessions.insert(session.to_owned(), entry);
+    }
+
+    /// Moves the entry of a session to a new key, replacing whatever the
+    /// new key had.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        match self.sessions.remove(from) {
+            Some(entry) => self.sessions.insert(to.to_owned(), entry),
+            None => self.sessions.remove(to),
+        };
+    }
+
+    /// Forgets the entry of a session.
+    pub fn remove(&mut self, session: &str) {
+        self.sessions.remove(session);
+    }
+}
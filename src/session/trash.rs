@@ -0,0 +1,296 @@
+//! A trash for removed sessions, so a wrong selection can be undone. Every
+//! trashed session gets its own directory in the vsm data directory, holding
+//! the session, its companion file and what vsm knew about it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::companion::companion_of;
+#[cfg(feature = "cli")]
+use super::companion::companion_path;
+use super::dirs::GROUP_SEPARATOR;
+use super::history::OpenRecord;
+use super::metadata::SessionMetadata;
+use crate::error::VsmRuntimeFault;
+
+/// Name of the trash directory inside the vsm data directory.
+const TRASH_DIR: &str = "trash";
+
+/// Name of the file describing a trashed session, inside its directory.
+const ENTRY_FILE: &str = "entry.toml";
+
+/// A trashed session.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Getters)]
+pub struct TrashEntry {
+    /// Session name
+    name: String,
+    /// Where the session file was before it was trashed
+    original: PathBuf,
+    /// When the session was trashed, in seconds since the unix epoch
+    deleted_at: u64,
+    /// The metadata of the session, restored along with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<SessionMetadata>,
+    /// The open history of the session, restored along with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history: Option<OpenRecord>,
+    /// Directory holding the trashed files
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+/// The vsm managed trash directory.
+#[derive(Debug, Getters)]
+pub struct Trash {
+    /// Path to the trash directory
+    dir: PathBuf,
+}
+
+impl Trash {
+    /// The trash kept in a vsm data directory.
+    ///
+    /// # Arguments
+    ///     * data_dir The vsm data directory.
+    #[must_use]
+    pub fn in_data_dir(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join(TRASH_DIR),
+        }
+    }
+
+    /// Moves a session and its companion file into the trash.
+    ///
+    /// # Arguments
+    ///     * session Path to the session file.
+    ///     * name The name of the session, see `SessionDirs::name_of`.
+    ///     * metadata The metadata of the session, if any.
+    ///     * history The open history of the session, if any.
+    ///     * now Current time, in seconds since the unix epoch.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if the files can not be moved.
+    pub fn put(
+        &self,
+        session: &Path,
+        name: &str,
+        metadata: Option<SessionMetadata>,
+        history: Option<OpenRecord>,
+        now: u64,
+    ) -> Result<TrashEntry, VsmRuntimeFault> {
+        // Grouped sessions are kept flat, the entry records the real name
+        let flat = name.replace(GROUP_SEPARATOR, "-");
+        let mut dir = self.dir.join(format!("{}.{}", flat, now));
+        for attempt in 1.. {
+            if !dir.exists() {
+                break;
+            }
+            dir = self.dir.join(format!("{}.{}.{}", flat, now, attempt));
+        }
+        let entry = TrashEntry {
+            name: name.to_owned(),
+            original: session.to_path_buf(),
+            deleted_at: now,
+            metadata: metadata.filter(|m| !m.is_empty()),
+            history,
+            dir,
+        };
+        let fault = |e: io::Error| VsmRuntimeFault::Trash {
+            msg: format!("{} => {}", session.display(), e),
+        };
+
+        fs::create_dir_all(&entry.dir).map_err(fault)?;
+        let contents = toml::to_string(&entry).map_err(|e| VsmRuntimeFault::Trash {
+            msg: format!("{} => {}", session.display(), e),
+        })?;
+        fs::write(entry.dir.join(ENTRY_FILE), contents).map_err(fault)?;
+        if let Some(companion) = companion_of(session) {
+            move_file(&companion, &entry.files_path(&companion)).map_err(fault)?;
+        }
+        move_file(session, &entry.files_path(session)).map_err(fault)?;
+        Ok(entry)
+    }
+
+    /// Every trashed session, most recently trashed first.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if the trash can not be read.
+    #[cfg(feature = "cli")]
+    pub fn entries(&self) -> Result<Vec<TrashEntry>, VsmRuntimeFault> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let fault = |e: String| VsmRuntimeFault::Trash {
+            msg: format!("{} => {}", self.dir.display(), e),
+        };
+        let mut entries: Vec<TrashEntry> = vec![];
+        for dir in fs::read_dir(&self.dir).map_err(|e| fault(e.to_string()))? {
+            let dir = dir.map_err(|e| fault(e.to_string()))?.path();
+            let Ok(contents) = fs::read_to_string(dir.join(ENTRY_FILE)) else {
+                continue;
+            };
+            let mut entry =
+                toml::from_str::<TrashEntry>(&contents).map_err(|e| fault(e.to_string()))?;
+            entry.dir = dir;
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then(a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    /// Moves a trashed session back to where it was.
+    ///
+    /// # Arguments
+    ///     * entry The trashed session.
+    ///     * force Overwrite a session, or a companion, that took its place.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if a session or a companion took its place
+    ///       without `force`, or the files can not be moved.
+    #[cfg(feature = "cli")]
+    pub fn restore(&self, entry: &TrashEntry, force: bool) -> Result<PathBuf, VsmRuntimeFault> {
+        let companion = companion_path(&entry.original);
+        let trashed_companion = entry.files_path(&companion);
+        let mut targets = vec![&entry.original];
+        if trashed_companion.exists() {
+            targets.push(&companion);
+        }
+        if let Some(taken) = targets.iter().find(|target| target.exists()) {
+            if !force {
+                return Err(VsmRuntimeFault::Trash {
+                    msg: format!("{} exists, pass --force to overwrite it", taken.display()),
+                });
+            }
+        }
+        let fault = |e: io::Error| VsmRuntimeFault::Trash {
+            msg: format!("{} => {}", entry.original.display(), e),
+        };
+        if let Some(dir) = entry.original.parent() {
+            fs::create_dir_all(dir).map_err(fault)?;
+        }
+        move_file(&entry.files_path(&entry.original), &entry.original).map_err(fault)?;
+        if trashed_companion.exists() {
+            move_file(&trashed_companion, &companion).map_err(fault)?;
+        }
+        fs::remove_dir_all(&entry.dir).map_err(fault)?;
+        Ok(entry.original.clone())
+    }
+
+    /// Permanently deletes a trashed session.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Trash if the files can not be deleted.
+    #[cfg(feature = "cli")]
+    pub fn purge(&self, entry: &TrashEntry) -> Result<(), VsmRuntimeFault> {
+        fs::remove_dir_all(&entry.dir).map_err(|e| VsmRuntimeFault::Trash {
+            msg: format!("{} => {}", entry.dir.display(), e),
+        })
+    }
+}
+
+impl TrashEntry {
+    /// Where a file of the session is kept inside the trash.
+    fn files_path(&self, file: &Path) -> PathBuf {
+        file.file_name()
+            .map_or_else(|| self.dir.clone(), |name| self.dir.join(name))
+    }
+}
+
+/// Moves a file, copying it when it has to cross filesystems.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::Trash;
+    use crate::session::SessionMetadata;
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_put_and_restore_session_with_companion() {
+        let sandbox = test_dir("trash");
+        let root = sandbox.path();
+        let sessions = root.join("sessions");
+        fs::create_dir_all(&sessions).expect("sandbox is created");
+        let session = sessions.join("api.vim");
+        fs::write(&session, "cd ~/api").expect("session is written");
+        fs::write(sessions.join("apix.vim"), "\" extra").expect("companion is written");
+        let mut metadata = SessionMetadata::default();
+        metadata.set_description("REST gateway");
+
+        let trash = Trash::in_data_dir(&root.join("data"));
+        trash
+            .put(&session, "api", Some(metadata), None, 10)
+            .expect("session is trashed");
+        assert!(!session.exists());
+        assert!(!sessions.join("apix.vim").exists());
+
+        let entries = trash.entries().expect("trash is read");
+        assert_eq!(entries.len(), 1);
+        let entry = entries.first().expect("one entry");
+        assert_eq!(entry.name(), "api");
+        assert_eq!(
+            entry
+                .metadata()
+                .as_ref()
+                .and_then(|m| m.description().clone()),
+            Some("REST gateway".to_owned())
+        );
+
+        fs::write(sessions.join("apix.vim"), "\" new").expect("a new companion is written");
+        trash
+            .restore(entry, false)
+            .expect_err("the new companion is not overwritten");
+        fs::remove_file(sessions.join("apix.vim")).expect("the new companion is removed");
+        fs::write(&session, "").expect("a new session takes the name");
+        trash
+            .restore(entry, false)
+            .expect_err("the new session is not overwritten");
+        trash.restore(entry, true).expect("session is restored");
+        assert_eq!(
+            fs::read_to_string(&session).expect("session is back"),
+            "cd ~/api"
+        );
+        assert_eq!(
+            fs::read_to_string(sessions.join("apix.vim")).expect("companion is back"),
+            "\" extra"
+        );
+        assert!(trash.entries().expect("trash is read").is_empty());
+    }
+
+    #[test]
+    fn test_put_leaves_sessions_ending_in_x_alone() {
+        let sandbox = test_dir("trash-x");
+        let root = sandbox.path();
+        let session = root.join("tm.vim");
+        fs::write(&session, "let SessionLoad = 1\n").expect("session is written");
+        fs::write(root.join("tmx.vim"), "let SessionLoad = 1\n").expect("session is written");
+
+        let trash = Trash::in_data_dir(&root.join("data"));
+        let entry = trash
+            .put(&session, "tm", None, None, 10)
+            .expect("session is trashed");
+        assert!(!session.exists());
+        assert!(root.join("tmx.vim").exists());
+
+        fs::write(root.join("tmx.vim"), "let SessionLoad = 1\n\" edited")
+            .expect("session is written");
+        trash.restore(&entry, false).expect("session is restored");
+        assert_eq!(
+            fs::read_to_string(root.join("tmx.vim")).expect("session is read"),
+            "let SessionLoad = 1\n\" edited"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Ranks sessions by how closely the working directory they `cd` into matches
+//! a directory, usually the one vsm is run from.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::parser::SessionFile;
+
+/// How the working directory of a session relates to a directory. Closer
+/// matches order first: an exact match, then sessions working in a parent of
+/// the directory, then sessions working below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Closeness {
+    /// The session works in the directory itself
+    Exact,
+    /// The session works this many levels above the directory
+    Ancestor(usize),
+    /// The session works this many levels below the directory
+    Descendant(usize),
+}
+
+impl Closeness {
+    /// Relates the working directory of a session to a directory.
+    ///
+    /// # Arguments
+    ///     * session_cwd The working directory of the session.
+    ///     * dir The directory to compare with.
+    ///
+    /// # Returns
+    ///     * None if neither directory holds the other.
+    #[must_use]
+    pub fn between(session_cwd: &Path, dir: &Path) -> Option<Self> {
+        if session_cwd == dir {
+            Some(Self::Exact)
+        } else if let Ok(rest) = dir.strip_prefix(session_cwd) {
+            Some(Self::Ancestor(rest.components().count()))
+        } else if let Ok(rest) = session_cwd.strip_prefix(dir) {
+            Some(Self::Descendant(rest.components().count()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Ranks the sessions whose working directory matches a directory, the
+/// closest first.
+///
+/// Sessions without a `cd` line, or that can not be read, are left out, and
+/// ties keep the order the sessions were given in.
+///
+/// # Arguments
+///     * sessions Paths to the session files.
+///     * dir The directory to match, usually the working directory.
+///     * home The home directory to expand `~` to.
+#[must_use]
+pub fn rank_by_directory(
+    sessions: &[PathBuf],
+    dir: &Path,
+    home: &str,
+) -> Vec<(PathBuf, Closeness)> {
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = canonical(dir);
+    let mut ranked: Vec<(PathBuf, Closeness)> = sessions
+        .iter()
+        .filter_map(|session| {
+            let file = SessionFile::from_path(session).ok()?;
+            let cwd = file.resolve_path(file.cwd().as_deref()?, home);
+            Closeness::between(&canonical(&cwd), &dir).map(|closeness| (session.clone(), closeness))
+        })
+        .collect();
+    ranked.sort_by_key(|&(_, closeness)| closeness);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{rank_by_directory, Closeness};
+    use crate::testing::test_dir;
+
+    #[test]
+    fn test_closeness_between() {
+        let code = Path::new("/home/al/code");
+        assert_eq!(Closeness::between(code, code), Some(Closeness::Exact));
+        assert_eq!(
+            Closeness::between(code, Path::new("/home/al/code/vsm/src")),
+            Some(Closeness::Ancestor(2))
+        );
+        assert_eq!(
+            Closeness::between(code, Path::new("/home/al")),
+            Some(Closeness::Descendant(1))
+        );
+        assert_eq!(Closeness::between(code, Path::new("/home/al/codex")), None);
+        assert!(Closeness::Exact < Closeness::Ancestor(1));
+        assert!(Closeness::Ancestor(3) < Closeness::Descendant(1));
+    }
+
+    #[test]
+    fn test_rank_by_directory_orders_closest_first() {
+        let sandbox = test_dir("workdir");
+        let root = sandbox.path();
+        let project = root.join("code").join("vsm");
+        fs::create_dir_all(project.join("src")).expect("Failed to create the sandbox");
+        let sessions = vec![
+            root.join("elsewhere.vim"),
+            root.join("code.vim"),
+            root.join("src.vim"),
+            root.join("vsm.vim"),
+            root.join("nocd.vim"),
+        ];
+        let cds = ["/nowhere", "~/code", "~/code/vsm/src", "~/code/vsm", ""];
+        for (session, cd) in sessions.iter().zip(cds) {
+            let contents = if cd.is_empty() {
+                String::new()
+            } else {
+                format!("cd {}\n", cd)
+            };
+            fs::write(session, contents).expect("Failed to write a session");
+        }
+
+        let ranked = rank_by_directory(&sessions, &project, &root.to_string_lossy());
+        let names: Vec<(String, Closeness)> = ranked
+            .into_iter()
+            .map(|(session, closeness)| {
+                (
+                    session
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    closeness,
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("vsm".to_owned(), Closeness::Exact),
+                ("code".to_owned(), Closeness::Ancestor(1)),
+                ("src".to_owned(), Closeness::Descendant(1)),
+            ]
+        );
+    }
+}
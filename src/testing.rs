@@ -0,0 +1,79 @@
+//! Fixtures shared by the unit tests.
+
+use tempfile::TempDir;
+
+/// Creates an empty directory for a test, which is removed when the returned
+/// handle is dropped, even if the test fails.
+///
+/// # Arguments
+///     * test Name of the test, it prefixes the directory name.
+pub fn test_dir(test: &str) -> TempDir {
+    tempfile::Builder::new()
+        .prefix(&format!("vsm-{}-", test))
+        .tempdir()
+        .expect("Failed to create the test directory")
+}
+
+/// Trimmed down output of Vim 9 with two tabs, the first split vertically.
+pub const VIM_SESSION: &str = r#"let SessionLoad = 1
+if &cp | set nocp | endif
+let s:so_save = &g:so | let s:siso_save = &g:siso | setg so=0 siso=0 | setl so=-1 siso=-1
+let v:this_session=expand("<sfile>:p")
+silent only
+silent tabonly
+cd ~/code/vsm
+if expand('%') == '' && !&modified && line('$') <= 1 && getline(1) == ''
+  let s:wipebuf = bufnr('%')
+endif
+let s:shortmess_save = &shortmess
+set shortmess=aoO
+badd +1 src/main.rs
+badd +12 src/my\ app.rs
+argglobal
+%argdel
+$argadd src/main.rs
+set stal=2
+tabnew +1 README.md
+tabrewind
+edit src/main.rs
+balt README.md
+let s:save_splitbelow = &splitbelow
+set splitbelow splitright
+wincmd _ | wincmd |
+vsplit
+1wincmd h
+wincmd w
+wincmd t
+exe 'vert 1resize ' . ((&columns * 89 + 89) / 179)
+argglobal
+setlocal fdm=manual
+silent! normal! zE
+keepjumps 1
+normal! 0
+wincmd w
+argglobal
+if bufexists(fnamemodify("src/my\ app.rs", ":p")) | buffer src/my\ app.rs | else | edit src/my\ app.rs | endif
+lcd ~/code/vsm/src
+wincmd w
+tabnext
+edit README.md
+argglobal
+tabnext 1
+set hlsearch
+doautoall SessionLoadPost
+unlet SessionLoad
+" vim: set ft=vim :
+"#;
+
+/// Neovim output with a terminal buffer and a plugin command it added.
+pub const NEOVIM_SESSION: &str = r#"let SessionLoad = 1
+let v:this_session=expand("<sfile>:p")
+cd ~/code/api
+badd +1 term://~/code/api//4242:/bin/zsh
+badd +3 main.go
+argglobal
+silent! argdel *
+edit main.go
+NERDTreeToggle
+tabnext 1
+"#;
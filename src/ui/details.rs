@@ -0,0 +1,140 @@
+//! Renders the detailed view of a single session for `vsm show`.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::VsmRuntimeFault;
+use crate::session::{SessionDetails, Split, TabPage};
+
+/// Renders the details of a session, as JSON or as a human readable report.
+/// The result is meant to be written to stdout as is.
+///
+/// # Errors
+///     * VsmRuntimeFault::Serialization if the details can not be represented
+///       as JSON.
+pub fn render_details(details: &SessionDetails, json: bool) -> Result<String, VsmRuntimeFault> {
+    if json {
+        return match serde_json::to_string_pretty(details) {
+            Ok(rendered) => Ok(format!("{}\n", rendered)),
+            Err(e) => Err(VsmRuntimeFault::Serialization { msg: e.to_string() }),
+        };
+    }
+    human(details).map_err(|e| VsmRuntimeFault::Serialization { msg: e.to_string() })
+}
+
+/// Builds the human readable report.
+fn human(details: &SessionDetails) -> Result<String, std::fmt::Error> {
+    let record = details.record();
+    let mut out = String::new();
+    writeln!(out, "Session    {}", record.name())?;
+    writeln!(out, "Path       {}", record.path().display())?;
+    writeln!(out, "Size       {} bytes", record.size())?;
+    writeln!(out, "Modified   {}", age(*record.mtime()))?;
+    writeln!(out, "Editor     {}", details.editor())?;
+    match details.cwd().as_deref() {
+        Some(cwd) if *details.cwd_exists() => writeln!(out, "Directory  {}", cwd)?,
+        Some(cwd) => writeln!(out, "Directory  {} (missing)", cwd)?,
+        None => writeln!(out, "Directory  -")?,
+    }
+
+    match *details.active_tab() {
+        Some(active) => writeln!(
+            out,
+            "Tabs       {} (active {})",
+            details.tabs().len(),
+            active
+        )?,
+        None => writeln!(out, "Tabs       {}", details.tabs().len())?,
+    }
+    for (number, tab) in (1..).zip(details.tabs()) {
+        tab_layout(&mut out, number, tab)?;
+    }
+
+    let missing = details.missing_buffers();
+    if missing == 0 {
+        writeln!(out, "Buffers    {}", details.buffers().len())?;
+    } else {
+        writeln!(
+            out,
+            "Buffers    {} ({} missing)",
+            details.buffers().len(),
+            missing
+        )?;
+    }
+    for status in details.buffers() {
+        let buffer = status.buffer();
+        let state = if *buffer.terminal() {
+            "terminal"
+        } else if *status.exists() {
+            "ok"
+        } else {
+            "missing"
+        };
+        match *buffer.line() {
+            Some(line) => writeln!(out, "  {:<9}{}:{}", state, buffer.path(), line)?,
+            None => writeln!(out, "  {:<9}{}", state, buffer.path())?,
+        }
+    }
+
+    if !details.unrecognized().is_empty() {
+        writeln!(out, "Unrecognized lines")?;
+        for line in details.unrecognized() {
+            writeln!(out, "  {:>4}  {}", line.number(), line.line())?;
+        }
+    }
+    Ok(out)
+}
+
+/// Writes the window layout of one tab page.
+fn tab_layout(out: &mut String, number: usize, tab: &TabPage) -> std::fmt::Result {
+    match tab.cwd().as_deref() {
+        Some(cwd) => writeln!(out, "  Tab {} (tcd {})", number, cwd)?,
+        None => writeln!(out, "  Tab {}", number)?,
+    }
+    for (window_number, window) in (1..).zip(tab.windows()) {
+        let mut notes: Vec<String> = vec![];
+        match *window.split() {
+            Some(Split::Horizontal) => notes.push("split".to_owned()),
+            Some(Split::Vertical) => notes.push("vsplit".to_owned()),
+            None => {}
+        }
+        if let Some(cwd) = window.cwd().as_deref() {
+            notes.push(format!("lcd {}", cwd));
+        }
+        let buffer = window.buffer().as_deref().unwrap_or("[No Name]");
+        if notes.is_empty() {
+            writeln!(out, "    window {}  {}", window_number, buffer)?;
+        } else {
+            writeln!(
+                out,
+                "    window {}  {} ({})",
+                window_number,
+                buffer,
+                notes.join(", ")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Describes how long ago a unix timestamp was, such as `3 days ago`.
+fn age(mtime: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let elapsed = now.saturating_sub(mtime);
+    let units: [(u64, &str); 4] = [
+        (86_400, "day"),
+        (3_600, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ];
+    for (seconds, unit) in units {
+        let count = elapsed.checked_div(seconds).unwrap_or_default();
+        if count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            return format!("{} {}{} ago", count, unit, plural);
+        }
+    }
+    "just now".to_owned()
+}
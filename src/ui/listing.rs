@@ -0,0 +1,140 @@
+//! Renders session listings for consumption by other programs, none of the
+//! output here is decorated by the logger.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::error::VsmRuntimeFault;
+use crate::session::{MetadataStore, SessionMetadata, SessionRecord};
+
+/// A session record along with its metadata, as listed in JSON.
+#[derive(Debug, Serialize)]
+struct Listed<'r> {
+    /// What is on disk
+    #[serde(flatten)]
+    record: &'r SessionRecord,
+    /// What was recorded about the session
+    #[serde(flatten)]
+    metadata: Option<&'r SessionMetadata>,
+}
+
+/// Machine readable output formats of the list sub-command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// A JSON array of session records
+    Json,
+    /// One tab separated record per line
+    Tsv,
+    /// One session name per line
+    Plain,
+    /// Session names separated by NUL bytes
+    Null,
+}
+
+impl ListFormat {
+    /// Names of all formats, as accepted on the command line.
+    pub const NAMES: [&'static str; 4] = ["json", "tsv", "plain", "null"];
+
+    /// Renders the records in this format. The result is meant to be written
+    /// to stdout as is. JSON and tsv include the description, tags and the
+    /// source directory. Tabs, newlines and backslashes in tsv fields are
+    /// escaped as `\t`, `\n` and `\\`, so every record is one line.
+    ///
+    /// # Arguments
+    ///     * records The sessions to list.
+    ///     * metadata The metadata of the sessions.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::Serialization if the records can not be
+    ///       represented as JSON.
+    pub fn render(
+        self,
+        records: &[SessionRecord],
+        metadata: &MetadataStore,
+    ) -> Result<String, VsmRuntimeFault> {
+        match self {
+            Self::Json => match serde_json::to_string_pretty(
+                &records
+                    .iter()
+                    .map(|record| Listed {
+                        record,
+                        metadata: metadata.get(record.key()),
+                    })
+                    .collect::<Vec<Listed<'_>>>(),
+            ) {
+                Ok(json) => Ok(format!("{}\n", json)),
+                Err(e) => Err(VsmRuntimeFault::Serialization { msg: e.to_string() }),
+            },
+            Self::Tsv => Ok(records
+                .iter()
+                .map(|r| {
+                    let listed = metadata.get(r.key());
+                    let fields = [
+                        r.name().clone(),
+                        r.path().display().to_string(),
+                        r.size().to_string(),
+                        r.mtime().to_string(),
+                        listed
+                            .and_then(|m| m.description().clone())
+                            .unwrap_or_default(),
+                        listed.map(|m| m.tags().join(",")).unwrap_or_default(),
+                        r.source().clone(),
+                    ];
+                    let escaped: Vec<String> =
+                        fields.iter().map(|field| tsv_field(field)).collect();
+                    format!("{}\n", escaped.join("\t"))
+                })
+                .collect()),
+            Self::Plain => Ok(records.iter().map(|r| format!("{}\n", r.name())).collect()),
+            Self::Null => Ok(records.iter().map(|r| format!("{}\0", r.name())).collect()),
+        }
+    }
+}
+
+/// Escapes a tsv field, so it holds no tab or line break of its own.
+fn tsv_field(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl FromStr for ListFormat {
+    type Err = VsmRuntimeFault;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            "plain" => Ok(Self::Plain),
+            "null" => Ok(Self::Null),
+            _ => Err(VsmRuntimeFault::Serialization {
+                msg: format!("Unknown list format '{}'", s),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::tsv_field;
+
+    #[test]
+    fn test_tsv_field_escapes_separators() {
+        assert_eq!(tsv_field("api"), "api");
+        assert_eq!(
+            tsv_field("REST\tgateway\r\nfor C:\\api"),
+            "REST\\tgateway\\r\\nfor C:\\\\api"
+        );
+    }
+}
@@ -1,6 +1,10 @@
 //! Expose ui modules
 
 mod aesthetic;
+mod details;
+mod listing;
 mod prompt;
 
-pub use prompt::UserPromptRenderer;
+pub use details::render_details;
+pub use listing::ListFormat;
+pub use prompt::{grouped, PickerEntry, Prompt, UserPromptRenderer};
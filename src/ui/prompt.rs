@@ -1,12 +1,221 @@
 //! Prompt User Interface
 
-use std::path::PathBuf;
+use std::fmt::{self, Debug, Display};
 
-use inquire::{MultiSelect, Select};
+use derive_getters::Getters;
+use inquire::{Confirm, MultiSelect, Select, Text};
 
 use super::aesthetic::get_render_config;
 use crate::error::VsmRuntimeFault;
-use crate::utils::extract_filename;
+use crate::session::{session_group, SessionMetadata};
+
+/// Orders the sessions of the current project first, then pinned sessions,
+/// keeping the order within them and the sessions of a group together.
+fn pinned_first(sessions: &[PickerEntry]) -> Vec<PickerEntry> {
+    grouped(sessions, true)
+}
+
+/// Keeps the sessions of a group together. Groups follow the order of their
+/// first session, and the order within a group is kept. The sessions of the
+/// current project always come first.
+///
+/// # Arguments
+///     * sessions The sessions in the order they were sorted in.
+///     * pinned_first Puts the pinned sessions before all others.
+#[must_use]
+pub fn grouped(sessions: &[PickerEntry], pinned_first: bool) -> Vec<PickerEntry> {
+    let key = |entry: &PickerEntry| {
+        (
+            entry.local,
+            pinned_first && entry.pinned,
+            entry.group().map(str::to_owned),
+        )
+    };
+    let mut firsts: Vec<(bool, bool, Option<String>)> = vec![];
+    for entry in sessions {
+        if !firsts.contains(&key(entry)) {
+            firsts.push(key(entry));
+        }
+    }
+    let mut ordered = sessions.to_vec();
+    ordered.sort_by_key(|entry| {
+        let (local, pinned, group) = key(entry);
+        let rank = firsts
+            .iter()
+            .position(|first| *first == (local, pinned, group.clone()));
+        (!local, !pinned, rank)
+    });
+    ordered
+}
+
+/// Marks pinned sessions in the pickers and listings.
+const PIN_MARKER: &str = "★";
+
+/// Marks the sessions of the current project in the pickers and listings.
+const LOCAL_MARKER: &str = "⌂";
+
+/// A session offered by the pickers. It is shown with its description and
+/// tags, so those can be filtered on as well.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct PickerEntry {
+    /// Session name
+    name: String,
+    /// One line description of the session
+    description: Option<String>,
+    /// Tags of the session
+    tags: Vec<String>,
+    /// Pinned sessions are offered first
+    pinned: bool,
+    /// Project-local session of the working directory, offered before all
+    /// others
+    local: bool,
+    /// Best match for the working directory, highlighted when the picker opens
+    suggested: bool,
+}
+
+impl PickerEntry {
+    /// Builds an entry for a session.
+    ///
+    /// # Arguments
+    ///     * name Session name.
+    ///     * metadata The metadata of the session, if any was recorded.
+    #[must_use]
+    pub fn new(name: &str, metadata: Option<&SessionMetadata>) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: metadata.and_then(|m| m.description().clone()),
+            tags: metadata.map(|m| m.tags().clone()).unwrap_or_default(),
+            pinned: metadata.is_some_and(|m| *m.pinned()),
+            local: false,
+            suggested: false,
+        }
+    }
+
+    /// Marks the entry as the project-local session of the working directory.
+    #[must_use]
+    pub const fn with_local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// Marks the entry as the session working closest to the working
+    /// directory, the single selection picker starts on it.
+    #[must_use]
+    pub const fn with_suggested(mut self, suggested: bool) -> Self {
+        self.suggested = suggested;
+        self
+    }
+
+    /// The group of the session, such as `work` for `work/api`.
+    #[must_use]
+    pub fn group(&self) -> Option<&str> {
+        session_group(&self.name)
+    }
+
+    /// The entry shown below the header of its group, named without the
+    /// group.
+    #[must_use]
+    pub fn without_group(&self) -> Self {
+        let mut entry = self.clone();
+        if let Some(group) = self.group() {
+            entry.name = self
+                .name
+                .get(group.len().saturating_add(1)..)
+                .unwrap_or_default()
+                .to_owned();
+        }
+        entry
+    }
+}
+
+impl Display for PickerEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.local {
+            write!(f, "{} ", LOCAL_MARKER)?;
+        }
+        if self.pinned {
+            write!(f, "{} ", PIN_MARKER)?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(description) = self.description.as_deref() {
+            write!(f, "  {}", description)?;
+        }
+        for tag in &self.tags {
+            write!(f, "  #{}", tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every question vsm asks the user. Implemented by `UserPromptRenderer`,
+/// tests and embedders can provide scripted answers instead.
+pub trait Prompt: Debug {
+    /// Presents the user with a single selection list of all installed
+    /// variations of vim found on the system.
+    ///
+    /// # Arguments
+    ///     * vim_variations A vector of the installed vim variations that were found on the system.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn vim_variant(&self, vim_variations: Vec<String>) -> Result<String, VsmRuntimeFault>;
+
+    /// Presents the user with a single selection list of all vim session files
+    /// found at the VIM_SESSIONS directory. The suggested session, if any, is
+    /// highlighted when the list opens.
+    ///
+    /// # Arguments
+    ///     * sessions: The sessions to choose from.
+    ///
+    /// # Returns
+    ///     * The name of the chosen session.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn session_open(&self, sessions: &[PickerEntry]) -> Result<String, VsmRuntimeFault>;
+
+    /// Presents the user with a multi-selection list of all vim session files
+    /// found at the VIM_SESSIONS directory.
+    ///
+    /// # Arguments
+    ///     * sessions: The sessions to choose from.
+    ///
+    /// # Returns
+    ///     * The names of the chosen sessions.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn session_remove(&self, sessions: &[PickerEntry]) -> Result<Vec<String>, VsmRuntimeFault>;
+
+    /// Asks the user for a line of text, such as a new session name.
+    ///
+    /// # Arguments
+    ///     * message: The question to ask.
+    ///     * initial: The initial value, which the user can edit.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn text(&self, message: &str, initial: &str) -> Result<String, VsmRuntimeFault>;
+
+    /// Asks the user a yes or no question, defaulting to no.
+    ///
+    /// # Arguments
+    ///     * message: The question to ask.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn confirm(&self, message: &str) -> Result<bool, VsmRuntimeFault>;
+
+    /// Asks the user to pick one of a few options.
+    ///
+    /// # Arguments
+    ///     * message: The question to ask.
+    ///     * options: The options to pick from.
+    ///
+    /// # Errors
+    ///     * VsmRuntimeFault::SelectionFailure
+    fn choose(&self, message: &str, options: &[&str]) -> Result<String, VsmRuntimeFault>;
+}
 
 /// Wrapper around the inquire library
 #[derive(Debug)]
@@ -15,23 +224,25 @@ pub struct UserPromptRenderer {
     help_message: String,
 }
 
+impl Default for UserPromptRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UserPromptRenderer {
     /// Initializes the global render theme, and holds prompt information.
+    #[must_use]
     pub fn new() -> Self {
         inquire::set_global_render_config(get_render_config());
         Self {
             help_message: "↑/↓ or k/j to move, enter to select, type to filter".to_owned(),
         }
     }
-    /// Presents the user with a single selection list of all installed
-    /// variations of vim found on the system.
-    ///
-    /// # Arguments
-    ///     * vim_variations A vector of the installed vim variations that were found on the system.
-    ///
-    /// # Errors
-    ///     * VsmRuntimeFault::SelectionFailure
-    pub fn vim_variant(&self, vim_variations: Vec<String>) -> Result<String, VsmRuntimeFault> {
+}
+
+impl Prompt for UserPromptRenderer {
+    fn vim_variant(&self, vim_variations: Vec<String>) -> Result<String, VsmRuntimeFault> {
         println!();
         match Select::new("Which variant would you like to use?", vim_variations)
             .with_vim_mode(true)
@@ -43,47 +254,65 @@ impl UserPromptRenderer {
         }
     }
 
-    /// Presents the user with a single selection list of all vim session files
-    /// found at the VIM_SESSIONS directory.
-    ///
-    /// # Arguments
-    ///     * sessions: A collection of stripped session file names.
-    ///
-    /// # Errors
-    ///     * VsmRuntimeFault::SelectionFailure
-    pub fn session_open(&self, sessions: &Vec<PathBuf>) -> Result<String, VsmRuntimeFault> {
+    fn session_open(&self, sessions: &[PickerEntry]) -> Result<String, VsmRuntimeFault> {
         println!();
-        let cleaned_file_names = extract_filename(sessions);
-        match Select::new("Which session would you like to open?", cleaned_file_names)
+        let ordered = pinned_first(sessions);
+        let cursor = ordered
+            .iter()
+            .position(|entry| entry.suggested)
+            .unwrap_or_default();
+        match Select::new("Which session would you like to open?", ordered)
+            .with_starting_cursor(cursor)
             .with_vim_mode(true)
             .with_help_message(self.help_message.as_str())
             .prompt()
         {
-            Ok(choice) => Ok(choice),
+            Ok(choice) => Ok(choice.name),
             Err(e) => Err(VsmRuntimeFault::SelectionFailure { msg: e.to_string() }),
         }
     }
 
-    /// Presents the user with a multi-selection list of all vim session files
-    /// found at the VIM_SESSIONS directory.
-    ///
-    /// # Arguments
-    ///     * sessions: A collection of stripped session file names.
-    ///
-    /// # Errors
-    ///     * VsmRuntimeFault::SelectionFailure
-    pub fn session_remove(&self, sessions: &Vec<PathBuf>) -> Result<Vec<String>, VsmRuntimeFault> {
+    fn session_remove(&self, sessions: &[PickerEntry]) -> Result<Vec<String>, VsmRuntimeFault> {
         println!();
-        let cleaned_file_names = extract_filename(sessions);
         match MultiSelect::new(
             "Which session(s) would you like to remove?",
-            cleaned_file_names,
+            pinned_first(sessions),
         )
         .with_vim_mode(true)
         .with_help_message("↑/↓ or k/j to move, space to select, type to filter")
         .prompt()
         {
-            Ok(selected_sessions) => Ok(selected_sessions),
+            Ok(selected_sessions) => Ok(selected_sessions
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect()),
+            Err(e) => Err(VsmRuntimeFault::SelectionFailure { msg: e.to_string() }),
+        }
+    }
+
+    fn text(&self, message: &str, initial: &str) -> Result<String, VsmRuntimeFault> {
+        println!();
+        match Text::new(message).with_initial_value(initial).prompt() {
+            Ok(answer) => Ok(answer),
+            Err(e) => Err(VsmRuntimeFault::SelectionFailure { msg: e.to_string() }),
+        }
+    }
+
+    fn confirm(&self, message: &str) -> Result<bool, VsmRuntimeFault> {
+        match Confirm::new(message).with_default(false).prompt() {
+            Ok(answer) => Ok(answer),
+            Err(e) => Err(VsmRuntimeFault::SelectionFailure { msg: e.to_string() }),
+        }
+    }
+
+    fn choose(&self, message: &str, options: &[&str]) -> Result<String, VsmRuntimeFault> {
+        println!();
+        match Select::new(message, options.to_vec())
+            .with_vim_mode(true)
+            .with_help_message(self.help_message.as_str())
+            .prompt()
+        {
+            Ok(choice) => Ok(choice.to_owned()),
             Err(e) => Err(VsmRuntimeFault::SelectionFailure { msg: e.to_string() }),
         }
     }
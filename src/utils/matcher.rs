@@ -0,0 +1,188 @@
+//! Resolves session names given on the command line against the session files
+//! found on disk.
+
+use glob::Pattern;
+
+use crate::error::VsmRuntimeFault;
+
+/// The outcome of resolving a user supplied name against the known sessions.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionMatch {
+    /// A single session was identified.
+    Found(String),
+    /// More than one session matched equally well, holds every candidate.
+    Ambiguous(Vec<String>),
+    /// No session matched at all.
+    NotFound,
+}
+
+/// Resolves a query against a collection of session names. Resolution is
+/// attempted in order of strictness: an exact match first, then a unique
+/// prefix, and lastly a case-insensitive fuzzy (subsequence) match.
+///
+/// # Arguments
+///     * query The name the user typed.
+///     * names Stripped session file names.
+pub fn resolve_session_name(query: &str, names: &[String]) -> SessionMatch {
+    if let Some(exact) = names.iter().find(|name| *name == query) {
+        return SessionMatch::Found(exact.clone());
+    }
+
+    let prefixed: Vec<String> = names
+        .iter()
+        .filter(|name| name.starts_with(query))
+        .cloned()
+        .collect();
+    if !prefixed.is_empty() {
+        return narrow(prefixed);
+    }
+
+    let mut fuzzy: Vec<(usize, String)> = names
+        .iter()
+        .filter_map(|name| fuzzy_span(query, name).map(|span| (span, name.clone())))
+        .collect();
+    // Tighter matches are the more likely candidates, list them first.
+    fuzzy.sort();
+    narrow(fuzzy.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Collects every session name matched by a name or a shell-style glob
+/// pattern such as `tmp-*`. Names without glob metacharacters must match
+/// exactly.
+///
+/// # Arguments
+///     * pattern A session name, or a glob pattern.
+///     * names Stripped session file names.
+///
+/// # Errors
+///     * VsmRuntimeFault::SessionResolution if the glob pattern is malformed.
+pub fn match_sessions(pattern: &str, names: &[String]) -> Result<Vec<String>, VsmRuntimeFault> {
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(names
+            .iter()
+            .filter(|name| *name == pattern)
+            .cloned()
+            .collect());
+    }
+    match Pattern::new(pattern) {
+        Ok(glob) => Ok(names
+            .iter()
+            .filter(|name| glob.matches(name))
+            .cloned()
+            .collect()),
+        Err(e) => Err(VsmRuntimeFault::SessionResolution {
+            msg: format!("Invalid pattern '{}' => {}", pattern, e),
+        }),
+    }
+}
+
+/// Turns a list of candidates into a `SessionMatch`.
+fn narrow(mut candidates: Vec<String>) -> SessionMatch {
+    match candidates.len() {
+        0 => SessionMatch::NotFound,
+        1 => candidates
+            .pop()
+            .map_or(SessionMatch::NotFound, SessionMatch::Found),
+        _ => SessionMatch::Ambiguous(candidates),
+    }
+}
+
+/// Checks if every character of the query appears in the candidate in order,
+/// ignoring case.
+///
+/// # Returns
+///     * Some(span) the distance between the first and last matched character.
+///     * None if the query is not a subsequence of the candidate.
+fn fuzzy_span(query: &str, candidate: &str) -> Option<usize> {
+    let mut wanted = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut start: Option<usize> = None;
+    for (idx, c) in candidate.chars().flat_map(char::to_lowercase).enumerate() {
+        match wanted.peek() {
+            Some(w) if *w == c => {
+                let first = *start.get_or_insert(idx);
+                wanted.next();
+                if wanted.peek().is_none() {
+                    return Some(idx.saturating_sub(first));
+                }
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{match_sessions, resolve_session_name, SessionMatch};
+
+    fn names() -> Vec<String> {
+        ["api", "api-gateway", "dotfiles", "vsm", "vsm-docs"]
+            .iter()
+            .map(|s| (*s).to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_match_wins_over_prefix() {
+        assert_eq!(
+            resolve_session_name("vsm", &names()),
+            SessionMatch::Found("vsm".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_unique_prefix() {
+        assert_eq!(
+            resolve_session_name("dot", &names()),
+            SessionMatch::Found("dotfiles".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_prefix() {
+        assert_eq!(
+            resolve_session_name("ap", &names()),
+            SessionMatch::Ambiguous(vec!["api".to_owned(), "api-gateway".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(
+            resolve_session_name("GTWY", &names()),
+            SessionMatch::Found("api-gateway".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(
+            resolve_session_name("zzz", &names()),
+            SessionMatch::NotFound
+        );
+    }
+
+    #[test]
+    fn test_match_sessions_with_glob() {
+        assert_eq!(
+            match_sessions("vsm*", &names()).expect("valid pattern"),
+            vec!["vsm".to_owned(), "vsm-docs".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_match_sessions_plain_name_is_exact() {
+        assert_eq!(
+            match_sessions("vs", &names()).expect("valid pattern"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_match_sessions_bad_pattern() {
+        match_sessions("[vsm", &names()).expect_err("malformed pattern");
+    }
+}
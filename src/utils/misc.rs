@@ -1,14 +1,10 @@
 //! Miscellaneous helper functions
-use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use std::io::{self, IsTerminal};
 
-/// Takes a vector of Path Buffers, stripes the path down to just the name of
-/// the filename with no extension. Returns the filename in a Vector of string.
-pub fn extract_filename(sessions: &Vec<PathBuf>) -> Vec<String> {
-    let mut cleaned_sessions: Vec<String> = vec![];
-    for session in sessions {
-        if let Some(file) = session.file_stem() {
-            cleaned_sessions.push(file.to_string_lossy().to_string());
-        }
-    }
-    cleaned_sessions
+/// Returns true if both stdin and stdout are attached to a terminal, meaning
+/// it is safe to render interactive prompts.
+#[cfg(feature = "cli")]
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
 }
@@ -1,9 +1,12 @@
 //! Expose public utilities
 
 mod fs;
+mod matcher;
 mod misc;
 mod shell;
 
 pub use fs::FilesystemManager;
-pub use misc::extract_filename;
-pub use shell::CommandExecutor;
+pub use matcher::{match_sessions, resolve_session_name, SessionMatch};
+#[cfg(feature = "cli")]
+pub use misc::is_interactive;
+pub use shell::{CommandExecutor, Shell};
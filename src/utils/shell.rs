@@ -2,11 +2,66 @@
 //! shell commands that requires to work. This module makes no attempt to work
 //! with Windows. Windows support is planned for the future.
 
+use std::fmt::Debug;
+use std::path::Path;
+
 use derive_getters::Getters;
 use log::{debug, error};
 
 use crate::error::VsmRuntimeFault;
 
+/// Everything vsm needs from the users shell. Implemented by
+/// `CommandExecutor`, tests and embedders can provide their own.
+pub trait Shell: Debug {
+    /// Identifies if a program is installed on the system.
+    ///
+    /// # Arguments
+    ///     * program The name of the installed program
+    ///
+    /// # Returns
+    ///     * true if program is installed
+    ///     * false if it is not
+    fn is_installed(&self, program: &str) -> bool;
+
+    /// Opens a session file with a vim variant, and waits for it to exit.
+    ///
+    /// # Arguments
+    ///     * vim_variant Variant of vim
+    ///     * shell_command required shell command the variant needs to open a session file
+    ///     * session_file absolute path to the session file to open
+    ///     * commands ex commands to run once the session is loaded, such as
+    ///       the autosave wiring
+    ///
+    /// # Returns
+    ///     * The exit code of the editor, None if it was killed by a signal
+    ///
+    /// # Errors
+    ///     * Consumes all process error messages, and returns a single VsmRuntimeFault error
+    fn open_editor_with_session(
+        &self,
+        vim_variant: &str,
+        shell_command: &str,
+        session_file: &str,
+        commands: &[String],
+    ) -> Result<Option<i32>, VsmRuntimeFault>;
+
+    /// Starts a vim variant with arbitrary arguments, and waits for it to exit.
+    ///
+    /// # Arguments
+    ///     * vim_variant Variant of vim
+    ///     * arguments Arguments passed to the editor as is
+    ///     * cwd Working directory of the editor, the current one if None
+    ///
+    /// # Errors
+    ///     * Consumes all process error messages, and returns a single VsmRuntimeFault error
+    fn launch_editor(
+        &self,
+        vim_variant: &str,
+        arguments: &[String],
+        cwd: Option<&Path>,
+    ) -> Result<(), VsmRuntimeFault>;
+}
+
 /// A posix compliant wrapper around std::process
 #[derive(Debug, Getters)]
 pub struct CommandExecutor {
@@ -33,17 +88,12 @@ impl CommandExecutor {
             |user_shell| Self { user_shell },
         )
     }
+}
 
+impl Shell for CommandExecutor {
     /// Uses the POSIX compliant command -v to identify if a program is
     /// installed on the system.
-    ///
-    /// # Arguments
-    ///     * program The name of the installed program
-    ///
-    /// # Returns
-    ///     * true if program is installed
-    ///     * false if it is not
-    pub fn is_installed(&self, program: &str) -> bool {
+    fn is_installed(&self, program: &str) -> bool {
         let cmd = format!("command -v {}", program);
         debug!("Executing {}", &cmd);
         let exit_status = std::process::Command::new(&self.user_shell)
@@ -55,38 +105,56 @@ impl CommandExecutor {
         match exit_status {
             Ok(status) => status.success(),
             Err(e) => {
-                error!("{}", e.to_string());
+                error!("{}", e);
                 false
             }
         }
     }
 
-    /// Uses the POSIX compliant command -v to identify if a program is
-    /// installed on the system.
-    ///
-    /// # Arguments
-    ///     * vim_variant Variant of vim
-    ///     * shell_command required shell command the variant needs to open a session file
-    ///     * session_file absolute path to the session file to open
-    ///
-    /// # Errors
-    ///     * Consumes all process error messages, and returns a single VsmRuntimeFault error
-    pub fn open_editor_with_session(
+    /// Spawns the variant directly, without going through the users shell.
+    /// The commands follow the session file, so they pass through the same
+    /// flags the variant needs for `-S`, such as the `--` of neovide.
+    fn open_editor_with_session(
         &self,
-        vim_variant: &String,
-        shell_command: &String,
-        session_file: &String,
-    ) -> Result<(), VsmRuntimeFault> {
+        vim_variant: &str,
+        shell_command: &str,
+        session_file: &str,
+        commands: &[String],
+    ) -> Result<Option<i32>, VsmRuntimeFault> {
         debug!(
-            "Executing: {} {} {}",
-            vim_variant, shell_command, session_file
+            "Executing: {} {} {} {:?}",
+            vim_variant, shell_command, session_file, commands
         );
         let spawned_process = std::process::Command::new(vim_variant)
             .args(shell_command.split_whitespace())
             .arg(session_file)
+            .args(commands.iter().flat_map(|command| ["-c", command.as_str()]))
             .spawn();
 
         match spawned_process {
+            Ok(mut process) => match process.wait() {
+                Ok(status) => Ok(status.code()),
+                Err(e) => Err(VsmRuntimeFault::CommandExecutor { msg: e.to_string() }),
+            },
+            Err(e) => Err(VsmRuntimeFault::CommandExecutor { msg: e.to_string() }),
+        }
+    }
+
+    /// Spawns the variant directly, without going through the users shell.
+    fn launch_editor(
+        &self,
+        vim_variant: &str,
+        arguments: &[String],
+        cwd: Option<&Path>,
+    ) -> Result<(), VsmRuntimeFault> {
+        debug!("Executing: {} {:?}", vim_variant, arguments);
+        let mut command = std::process::Command::new(vim_variant);
+        command.args(arguments);
+        if let Some(dir) = cwd {
+            command.current_dir(dir);
+        }
+
+        match command.spawn() {
             Ok(mut process) => match process.wait() {
                 Ok(_) => Ok(()),
                 Err(e) => Err(VsmRuntimeFault::CommandExecutor { msg: e.to_string() }),
@@ -100,7 +168,7 @@ impl CommandExecutor {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use super::CommandExecutor;
+    use super::{CommandExecutor, Shell};
 
     #[test]
     fn test_is_program_installed_with_installed_program() {
@@ -1,8 +1,68 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
 use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+/// Creates a home directory whose config.toml selects vim, next to a session
+/// directory holding the given (empty) sessions.
+fn home(sessions: &[&str]) -> TempDir {
+    let home = tempfile::Builder::new()
+        .prefix("vsm-e2e-")
+        .tempdir()
+        .expect("home directory is created");
+    let config_dir = home.path().join(".config").join("vsm");
+    fs::create_dir_all(&config_dir).expect("config directory is created");
+    fs::write(
+        config_dir.join("config.toml"),
+        "[vim_variant]\nactive_variant = \"vim\"\nshell_command = \"-S\"\n",
+    )
+    .expect("config is written");
+    fs::create_dir_all(home.path().join("sessions")).expect("session directory is created");
+    for session in sessions {
+        fs::write(home.path().join("sessions").join(session), "").expect("session is written");
+    }
+    home
+}
+
+/// Runs the vsm binary with nothing but the given home directory in its
+/// environment.
+fn vsm(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vsm"))
+        .args(args)
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .env("HOME", home)
+        .env("VIM_SESSIONS", home.join("sessions"))
+        .current_dir(home)
+        .output()
+        .expect("vsm is spawned")
+}
+
+#[test]
+fn list_prints_machine_readable_sessions() {
+    let home = home(&["api.vim", "web.vim", "notes.txt"]);
+    let output = vsm(home.path(), &["list", "--format", "tsv"]);
+    assert!(output.status.success());
+    let names: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split('\t').next())
+        .map(str::to_owned)
+        .collect();
+    assert_eq!(names, vec!["api".to_owned(), "web".to_owned()]);
+}
+
+#[test]
+fn open_unknown_session_fails() {
+    let home = home(&["api.vim"]);
+    let output = vsm(home.path(), &["open", "nope"]);
+    assert_eq!(output.status.code(), Some(1));
+}
 
-/// TODO: Example end to end test, feel free to replace it with something meaningful.
 #[test]
-fn end_to_end() {
-    let x = 43;
-    assert_eq!(x, 43);
+fn remove_without_sessions_fails() {
+    let home = home(&[]);
+    let output = vsm(home.path(), &["remove", "tmp-*", "--yes"]);
+    assert_eq!(output.status.code(), Some(1));
 }
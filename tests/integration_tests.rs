@@ -1,8 +1,70 @@
+use std::fs;
+
 use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use vsm::{SessionCatalog, SessionMatch};
+
+/// Creates a fresh session directory holding the given (empty) sessions, it
+/// is removed when dropped.
+fn session_dir(test: &str, sessions: &[&str]) -> TempDir {
+    let dir = tempfile::Builder::new()
+        .prefix(&format!("vsm-{}-", test))
+        .tempdir()
+        .expect("session directory is created");
+    for session in sessions {
+        fs::write(dir.path().join(session), "").expect("session file is written");
+    }
+    dir
+}
+
+#[test]
+fn catalog_lists_only_vim_files() {
+    let dir = session_dir("catalog-list", &["api.vim", "vsm.vim", "notes.txt"]);
+    let catalog = SessionCatalog::new(
+        &dir.path().to_string_lossy(),
+        &dir.path().join("data").to_string_lossy(),
+        "/home/al",
+    );
+    assert_eq!(
+        catalog.names().expect("catalog is readable"),
+        vec!["api".to_owned(), "vsm".to_owned()]
+    );
+    let sessions = catalog.sessions().expect("catalog is readable");
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions.iter().all(|session| session.path().is_absolute()));
+}
 
-/// TODO: Replace with something real
 #[test]
-fn integrate() {
-    let x = 42;
-    assert_eq!(x, 42);
+fn catalog_resolves_and_removes() {
+    let dir = session_dir(
+        "catalog-remove",
+        &["api.vim", "api-gateway.vim", "tmp-1.vim"],
+    );
+    // The trash is kept apart, so the removed session is not read back
+    let data = session_dir("catalog-remove-data", &[]);
+    let catalog = SessionCatalog::new(
+        &dir.path().to_string_lossy(),
+        &data.path().to_string_lossy(),
+        "/home/al",
+    );
+    assert_eq!(
+        catalog.resolve("gate").expect("catalog is readable"),
+        SessionMatch::Found("api-gateway".to_owned())
+    );
+    for session in catalog.matching("tmp-*").expect("valid pattern") {
+        catalog.remove(&session).expect("session is removed");
+    }
+    assert_eq!(
+        catalog
+            .find("tmp-1")
+            .expect("catalog is readable")
+            .is_none(),
+        true
+    );
+    assert_eq!(
+        fs::read_dir(data.path().join("trash"))
+            .expect("trash is readable")
+            .count(),
+        1
+    );
 }